@@ -0,0 +1,218 @@
+//! Serialization of parsed systems for use by other tools.
+
+use json::JsonValue;
+
+use crate::{Kind, Object, Position, System};
+
+/// Bumped whenever a change to the export could break existing consumers.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Serialize `systems` as JSON, wrapped in an envelope identifying the format version and
+/// the program that generated it.
+pub fn to_json(systems: &[System]) -> String {
+    let envelope = json::object! {
+        format: "scansector",
+        version: FORMAT_VERSION,
+        generator: concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")),
+        systems: systems.iter().map(system_json).collect::<Vec<_>>(),
+    };
+
+    envelope.pretty(2)
+}
+
+fn system_json(system: &System) -> JsonValue {
+    json::object! {
+        name: system.name.as_str(),
+        location: system.location.map(position_json),
+        mission: system.mission,
+        objects: system
+            .objects
+            .iter()
+            .map(|o| object_json(system, o))
+            .collect::<Vec<_>>(),
+    }
+}
+
+fn object_json(system: &System, object: &Object) -> JsonValue {
+    json::object! {
+        name: object.name.as_str(),
+        kind: match object.kind {
+            Kind::Planet => "planet",
+            Kind::Entity => "entity",
+        },
+        type: object.planet_type.as_deref().or(object.entity_type.as_deref()),
+        faction: object.faction.as_deref(),
+        position: position_json(object.pos),
+        orbits: object
+            .parent
+            .and_then(|i| system.objects.get(i))
+            .map(|p| p.name.as_str()),
+        mission: object.mission,
+        discovered: object.discovered,
+        conditions: object
+            .market
+            .as_ref()
+            .map(|m| m.conditions.clone())
+            .unwrap_or_default(),
+    }
+}
+
+/// Serialize the objects in `systems` as CSV, one row per object, for loading into a
+/// spreadsheet.  Market conditions are joined with semicolons.
+pub fn to_csv(systems: &[System]) -> String {
+    let mut csv = String::from("system,object,kind,x,y,mission,discovered,conditions\n");
+    for system in systems {
+        for object in &system.objects {
+            let conditions = object
+                .market
+                .as_ref()
+                .map(|m| m.conditions.join(";"))
+                .unwrap_or_default();
+            let kind = match object.kind {
+                Kind::Planet => "planet",
+                Kind::Entity => "entity",
+            };
+            csv.push_str(&format!(
+                "{},{},{kind},{},{},{},{},{}\n",
+                csv_field(&system.name),
+                csv_field(&object.name),
+                object.pos.x,
+                object.pos.y,
+                object.mission,
+                object.discovered,
+                csv_field(&conditions),
+            ));
+        }
+    }
+    csv
+}
+
+/// Serialize the gate network as a Graphviz DOT graph, joining every pair of systems with a
+/// gate, as gates reach any other.  With `weighted`, edges are labelled with the distance
+/// between the systems in hyperspace, where both locations are known, and given it as their
+/// preferred length in tens of thousands of units for layouts like `neato` to honour.
+pub fn to_dot(systems: &[System], weighted: bool) -> String {
+    let gates: Vec<&System> = systems.iter().filter(|s| s.has_gate()).collect();
+    let mut dot = String::from("graph gates {\n");
+    for system in &gates {
+        dot.push_str(&format!("    {};\n", dot_id(&system.name)));
+    }
+    for (i, a) in gates.iter().enumerate() {
+        for b in &gates[i + 1..] {
+            let distance = match (a.location, b.location) {
+                (Some(from), Some(to)) if weighted => {
+                    let distance = from.distance(&to);
+                    format!(" [label=\"{distance:.0}\", len={:.2}]", distance / 10_000.0)
+                }
+                _ => String::new(),
+            };
+            dot.push_str(&format!(
+                "    {} -- {}{distance};\n",
+                dot_id(&a.name),
+                dot_id(&b.name)
+            ));
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Quote a name as a DOT identifier.
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quote a field if it contains anything CSV treats specially.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn position_json(pos: Position) -> JsonValue {
+    json::object! { x: pos.x, y: pos.y }
+}
+
+/// An affine mapping from game units in a system to pixels in an image of it, with the image's
+/// y axis pointing down.  Pixel coordinates are `x * scale_x + offset_x` and
+/// `y * scale_y + offset_y`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageTransform {
+    pub width: u32,
+    pub height: u32,
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
+
+impl ImageTransform {
+    /// Map the square of game units `extent` either side of the system's centre onto an image
+    /// of `width` by `height` pixels, as the game's own system map is framed.
+    pub fn centered(extent: f64, width: u32, height: u32) -> Self {
+        let (w, h) = (f64::from(width), f64::from(height));
+        Self {
+            width,
+            height,
+            scale_x: w / (2.0 * extent),
+            scale_y: -h / (2.0 * extent),
+            offset_x: w / 2.0,
+            offset_y: h / 2.0,
+        }
+    }
+
+    /// Fit every object in `system` into the image, leaving a margin of `padding` game units.
+    pub fn fit(system: &System, padding: f64, width: u32, height: u32) -> Self {
+        let extent = system
+            .objects
+            .iter()
+            .map(|o| o.pos.x.abs().max(o.pos.y.abs()))
+            .fold(0.0, f64::max);
+        Self::centered(extent + padding, width, height)
+    }
+
+    pub fn apply(&self, pos: Position) -> (f64, f64) {
+        (
+            pos.x * self.scale_x + self.offset_x,
+            pos.y * self.scale_y + self.offset_y,
+        )
+    }
+}
+
+/// Serialize the pixel coordinates of the objects in `system` under `transform`, along with
+/// the transform itself, for overlaying on a screenshot of the system map.
+pub fn to_overlay_json(system: &System, transform: &ImageTransform) -> String {
+    let objects: Vec<JsonValue> = system
+        .objects
+        .iter()
+        .map(|object| {
+            let (x, y) = transform.apply(object.pos);
+            json::object! {
+                name: object.name.as_str(),
+                x: x,
+                y: y,
+                mission: object.mission,
+            }
+        })
+        .collect();
+
+    let envelope = json::object! {
+        format: "scansector-overlay",
+        version: FORMAT_VERSION,
+        generator: concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION")),
+        system: system.name.as_str(),
+        transform: json::object! {
+            width: transform.width,
+            height: transform.height,
+            scale_x: transform.scale_x,
+            scale_y: transform.scale_y,
+            offset_x: transform.offset_x,
+            offset_y: transform.offset_y,
+        },
+        objects: objects,
+    };
+
+    envelope.pretty(2)
+}
@@ -0,0 +1,3003 @@
+//! Extraction of star systems and their contents from Starsector saves.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use roxmltree::Node;
+
+pub mod export;
+pub mod report;
+pub mod sheet;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Position {
+    pub fn distance(&self, other: &Position) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Planet,
+    Entity,
+}
+
+#[derive(Clone, Debug)]
+pub struct Object {
+    pub name: String,
+    pub kind: Kind,
+    pub pos: Position,
+    pub mission: bool,
+    /// What's known about each mission the object is important to.
+    pub missions: Vec<Mission>,
+    /// The XStream id of this object, used to resolve references to it elsewhere in the save.
+    pub id: Option<String>,
+    /// The id the game and mods refer to this object by, such as `jangala`.
+    pub internal_id: Option<String>,
+    /// The index within the system of the object this one orbits, if any.
+    pub parent: Option<usize>,
+    /// The object's orbit, as far as the save describes it.
+    pub orbit: Option<Orbit>,
+    /// Whether the player knows about this object.  Undiscovered objects are still in the save,
+    /// flagged as discoverable until the player's sensors pick them up.
+    pub discovered: bool,
+    /// The radius of a planet or star.
+    pub radius: Option<f64>,
+    /// The planet type id, such as `terran` or `gas_giant`.
+    pub planet_type: Option<String>,
+    /// The custom entity type id of an entity, such as `derelict_probe`.
+    pub entity_type: Option<String>,
+    /// The atmosphere, from the market's conditions.
+    pub atmosphere: Option<Atmosphere>,
+    /// The temperature band, from the market's conditions.
+    pub temperature: Option<Temperature>,
+    pub market: Option<Market>,
+    /// The drop groups salvaging this object can yield, such as `blueprints` or `weapons2`.
+    pub salvage: Vec<String>,
+    /// Whether the object keeps one face to what it orbits, not spinning of its own accord.
+    pub tidally_locked: bool,
+    /// The decorative ring bands around a planet, centred on it.
+    pub rings: Vec<Terrain>,
+    /// The id of the faction owning this object, such as `hegemony`.
+    pub faction: Option<String>,
+    /// The owning faction's relationship with the player, from -1 (vengeful) to 1
+    /// (cooperative).
+    pub relationship: Option<f64>,
+    /// The object's tags, such as `beacon_high` on a warning beacon.
+    pub tags: Vec<String>,
+    /// Whether the player has scanned the object, as they do gates with a Janus Device.
+    pub scanned: bool,
+    /// What the save has for the object, as written.
+    pub raw: RawData,
+}
+
+/// An object's data as it appears in the save, for troubleshooting parsing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RawData {
+    /// The `j0` JSON holding the object's name, among other things.
+    pub j0: Option<String>,
+    /// The attributes of the object's element, as name-value pairs.
+    pub attributes: Vec<(String, String)>,
+    /// Whether the `j0` couldn't be read, so the object's name was found elsewhere.
+    pub unreadable: bool,
+}
+
+impl RawData {
+    /// The `j0` JSON indented for reading, or as written where it isn't valid JSON.
+    pub fn pretty_j0(&self) -> Option<String> {
+        let j0 = self.j0.as_deref()?;
+        Some(json::parse(j0).map_or_else(|_| j0.to_string(), |value| value.pretty(2)))
+    }
+}
+
+/// Details of a mission an object is important to, as far as the save records them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mission {
+    /// The mission or reason id, such as `survey` or `bounty`.
+    pub kind: Option<String>,
+    /// The faction offering the mission.
+    pub faction: Option<String>,
+    /// The credit reward.
+    pub reward: Option<f64>,
+}
+
+/// A circular orbit around the object's parent.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Orbit {
+    /// How far round the orbit the object is, in degrees anticlockwise from east.
+    pub angle: Option<f64>,
+    /// The distance from what's orbited.
+    pub radius: Option<f64>,
+    /// How many days the object takes to go round once.
+    pub period: Option<f64>,
+}
+
+impl Orbit {
+    /// Where round the orbit the object will be after `days`, in degrees.
+    pub fn angle_after(&self, days: f64) -> Option<f64> {
+        let period = self.period.filter(|&p| p != 0.0)?;
+        Some((self.angle? + 360.0 * days / period).rem_euclid(360.0))
+    }
+}
+
+/// Broad kinds of mission, going by their ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissionType {
+    Bounty,
+    Survey,
+    Delivery,
+    Other,
+}
+
+impl MissionType {
+    pub const ALL: [MissionType; 4] = [
+        MissionType::Bounty,
+        MissionType::Survey,
+        MissionType::Delivery,
+        MissionType::Other,
+    ];
+
+    /// Classify a mission or reason id, which mods and game versions spell in various ways.
+    pub fn from_kind(kind: &str) -> Self {
+        let kind = kind.to_lowercase();
+        if kind.contains("bounty") {
+            MissionType::Bounty
+        } else if kind.contains("survey") || kind.contains("scan") {
+            MissionType::Survey
+        } else if kind.contains("deliver") || kind.contains("cargo") {
+            MissionType::Delivery
+        } else {
+            MissionType::Other
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MissionType::Bounty => "Bounty",
+            MissionType::Survey => "Survey",
+            MissionType::Delivery => "Delivery",
+            MissionType::Other => "Other",
+        }
+    }
+}
+
+impl Mission {
+    pub fn mission_type(&self) -> MissionType {
+        self.kind
+            .as_deref()
+            .map_or(MissionType::Other, MissionType::from_kind)
+    }
+}
+
+impl Object {
+    /// The type of the first mission the object is important to, if any.
+    pub fn mission_type(&self) -> Option<MissionType> {
+        self.missions.first().map(Mission::mission_type)
+    }
+
+    pub fn size(&self) -> Option<Size> {
+        self.radius.map(Size::from_radius)
+    }
+
+    pub fn survey(&self) -> Option<SurveyLevel> {
+        self.market.as_ref()?.survey
+    }
+
+    /// The size of the object's colony, if it has one.
+    pub fn market_size(&self) -> Option<u8> {
+        self.market.as_ref()?.size
+    }
+
+    /// Whether this is a planet of a type with a breathable, Earth-like surface.
+    pub fn terran_like(&self) -> bool {
+        self.planet_type
+            .as_deref()
+            .is_some_and(|t| TERRAN_TYPES.contains(&t))
+    }
+
+    pub fn category(&self) -> Category {
+        let entity_type = self.entity_type.as_deref().unwrap_or("");
+        match self.kind {
+            Kind::Planet => Category::Planet,
+            Kind::Entity if entity_type.contains("station") => Category::Station,
+            Kind::Entity if entity_type.contains("jump_point") => Category::JumpPoint,
+            Kind::Entity => Category::Entity,
+        }
+    }
+
+    /// Whether this is one of the Domain-era gates, whether or not it's been reactivated.
+    pub fn is_gate(&self) -> bool {
+        self.entity_type.as_deref() == Some(GATE_ENTITY)
+    }
+
+    /// Whether the gate can be travelled through, if this is a gate.
+    pub fn gate_state(&self) -> Option<GateState> {
+        self.is_gate().then_some(if self.scanned {
+            GateState::Active
+        } else {
+            GateState::Inactive
+        })
+    }
+
+    /// Whether this is an empty stable location, with nothing built there yet.
+    pub fn is_stable_location(&self) -> bool {
+        self.entity_type.as_deref() == Some(STABLE_LOCATION_ENTITY)
+    }
+
+    /// Whether this is a relay, buoy or array, which take up a stable location the game replaces
+    /// with them.
+    pub fn occupies_stable_location(&self) -> bool {
+        self.infrastructure().is_some()
+    }
+
+    /// The kind of relay, buoy or array this is, if it's one.
+    pub fn infrastructure(&self) -> Option<Infrastructure> {
+        Infrastructure::from_entity_type(self.entity_type.as_deref()?)
+    }
+
+    /// Whether this is infrastructure left from the Domain, rather than a makeshift one.
+    pub fn domain_era(&self) -> bool {
+        self.infrastructure().is_some()
+            && !self
+                .entity_type
+                .as_deref()
+                .is_some_and(|t| t.ends_with("_makeshift"))
+    }
+
+    /// Whether the infrastructure is working for a faction.  Nobody gains from one that's
+    /// unclaimed or left neutral.
+    pub fn operational(&self) -> bool {
+        self.infrastructure().is_some() && self.faction.as_deref().is_some_and(|f| f != "neutral")
+    }
+
+    /// The danger a warning beacon warns of, if this is one.
+    pub fn beacon_danger(&self) -> Option<Danger> {
+        if self.entity_type.as_deref() != Some(BEACON_ENTITY) {
+            return None;
+        }
+        self.tags.iter().find_map(|tag| Danger::from_tag(tag))
+    }
+
+    /// Whether salvaging this object can turn up blueprints.
+    pub fn has_blueprints(&self) -> bool {
+        self.salvage.iter().any(|group| group.contains("blueprint"))
+    }
+
+    /// What to follow the object by from one save to the next: its id where the save has
+    /// one, or else its name.
+    pub fn trail_key(&self) -> &str {
+        self.internal_id.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Resources to be salvaged from the object once, going by its salvage groups and what
+    /// sort of entity it is.
+    pub fn one_time_resources(&self) -> impl Iterator<Item = Resource> + '_ {
+        let ids: Vec<&str> = self
+            .salvage
+            .iter()
+            .map(String::as_str)
+            .chain(self.entity_type.as_deref())
+            .collect();
+        Resource::ALL
+            .into_iter()
+            .filter(move |resource| ids.iter().any(|id| resource.yielded_by(id)))
+    }
+
+    /// The class of star this is, if it's a star.
+    pub fn star_class(&self) -> Option<StarClass> {
+        StarClass::from_planet_type(self.planet_type.as_deref()?)
+    }
+
+    pub fn unique(&self) -> Option<Unique> {
+        Unique::from_entity_type(self.entity_type.as_deref()?)
+    }
+
+    pub fn standing(&self) -> Option<Standing> {
+        if self.faction.as_deref() == Some(PLAYER_FACTION) {
+            return Some(Standing::Friendly);
+        }
+        self.relationship.map(Standing::from_relationship)
+    }
+}
+
+/// The atmosphere of a planet, as described by its conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Atmosphere {
+    Breathable,
+    Thin,
+    Dense,
+    Toxic,
+    Vacuum,
+}
+
+impl Atmosphere {
+    pub const ALL: [Atmosphere; 5] = [
+        Atmosphere::Breathable,
+        Atmosphere::Thin,
+        Atmosphere::Dense,
+        Atmosphere::Toxic,
+        Atmosphere::Vacuum,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Atmosphere::Breathable => "Breathable",
+            Atmosphere::Thin => "Thin",
+            Atmosphere::Dense => "Dense",
+            Atmosphere::Toxic => "Toxic",
+            Atmosphere::Vacuum => "No atmosphere",
+        }
+    }
+
+    /// The atmosphere described by a set of conditions, letting the more specific atmosphere
+    /// conditions take precedence over `habitable`.
+    pub fn from_conditions<'a>(conditions: impl IntoIterator<Item = &'a str>) -> Option<Self> {
+        conditions
+            .into_iter()
+            .filter_map(|c| match c {
+                "habitable" => Some(Atmosphere::Breathable),
+                "thin_atmosphere" => Some(Atmosphere::Thin),
+                "dense_atmosphere" => Some(Atmosphere::Dense),
+                "toxic_atmosphere" => Some(Atmosphere::Toxic),
+                "no_atmosphere" => Some(Atmosphere::Vacuum),
+                _ => None,
+            })
+            .max()
+    }
+}
+
+/// The temperature band of a planet, as described by its conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Temperature {
+    VeryCold,
+    Cold,
+    Temperate,
+    Hot,
+    VeryHot,
+}
+
+impl Temperature {
+    pub const ALL: [Temperature; 5] = [
+        Temperature::VeryCold,
+        Temperature::Cold,
+        Temperature::Temperate,
+        Temperature::Hot,
+        Temperature::VeryHot,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Temperature::VeryCold => "Very cold",
+            Temperature::Cold => "Cold",
+            Temperature::Temperate => "Temperate",
+            Temperature::Hot => "Hot",
+            Temperature::VeryHot => "Very hot",
+        }
+    }
+
+    /// The temperature described by a set of conditions.  Planets without a temperature
+    /// condition are temperate.
+    pub fn from_conditions<'a>(conditions: impl IntoIterator<Item = &'a str>) -> Self {
+        conditions
+            .into_iter()
+            .find_map(|c| match c {
+                "very_cold" => Some(Temperature::VeryCold),
+                "cold" => Some(Temperature::Cold),
+                "hot" => Some(Temperature::Hot),
+                "very_hot" => Some(Temperature::VeryHot),
+                _ => None,
+            })
+            .unwrap_or(Temperature::Temperate)
+    }
+}
+
+/// Planet types with Earth-like surfaces.
+pub const TERRAN_TYPES: &[&str] = &[
+    "terran",
+    "terran-eccentric",
+    "jungle",
+    "water",
+    "arid",
+    "tundra",
+];
+
+/// The classes of star, by their planet type id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StarClass {
+    BlueGiant,
+    BlueSupergiant,
+    White,
+    Yellow,
+    Orange,
+    RedGiant,
+    RedSupergiant,
+    RedDwarf,
+    BrownDwarf,
+    Neutron,
+    BlackHole,
+}
+
+impl StarClass {
+    pub fn from_planet_type(planet_type: &str) -> Option<Self> {
+        match planet_type {
+            "star_blue_giant" => Some(StarClass::BlueGiant),
+            "star_blue_supergiant" => Some(StarClass::BlueSupergiant),
+            "star_white" => Some(StarClass::White),
+            "star_yellow" => Some(StarClass::Yellow),
+            "star_orange" => Some(StarClass::Orange),
+            "star_red_giant" => Some(StarClass::RedGiant),
+            "star_red_supergiant" => Some(StarClass::RedSupergiant),
+            "star_red_dwarf" => Some(StarClass::RedDwarf),
+            "star_browndwarf" => Some(StarClass::BrownDwarf),
+            "star_neutron" => Some(StarClass::Neutron),
+            "black_hole" => Some(StarClass::BlackHole),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StarClass::BlueGiant => "Blue Giant",
+            StarClass::BlueSupergiant => "Blue Supergiant",
+            StarClass::White => "White Star",
+            StarClass::Yellow => "Yellow Star",
+            StarClass::Orange => "Orange Star",
+            StarClass::RedGiant => "Red Giant",
+            StarClass::RedSupergiant => "Red Supergiant",
+            StarClass::RedDwarf => "Red Dwarf",
+            StarClass::BrownDwarf => "Brown Dwarf",
+            StarClass::Neutron => "Neutron Star",
+            StarClass::BlackHole => "Black Hole",
+        }
+    }
+
+    /// The system-wide hazard the star imposes, if any.
+    pub fn hazard(self) -> Option<StellarHazard> {
+        match self {
+            StarClass::BlackHole => Some(StellarHazard::BlackHole),
+            StarClass::Neutron => Some(StellarHazard::Pulsar),
+            _ => None,
+        }
+    }
+
+    /// How much light the star puts out for solar arrays to work with.
+    pub fn solar_power(self) -> SolarPower {
+        match self {
+            StarClass::BlueGiant | StarClass::BlueSupergiant | StarClass::White => {
+                SolarPower::Strong
+            }
+            StarClass::Yellow | StarClass::Orange | StarClass::RedGiant => SolarPower::Adequate,
+            StarClass::RedSupergiant | StarClass::RedDwarf | StarClass::Neutron => SolarPower::Poor,
+            StarClass::BrownDwarf | StarClass::BlackHole => SolarPower::None,
+        }
+    }
+}
+
+/// Stars dangerous to operate around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StellarHazard {
+    BlackHole,
+    /// A neutron star, whose beams sweep the system.
+    Pulsar,
+}
+
+impl StellarHazard {
+    pub const ALL: [StellarHazard; 2] = [StellarHazard::BlackHole, StellarHazard::Pulsar];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StellarHazard::BlackHole => "Black Hole",
+            StellarHazard::Pulsar => "Pulsar",
+        }
+    }
+
+    /// What the hazard does to fleets, as the game describes it.
+    pub fn effect(self) -> &'static str {
+        match self {
+            StellarHazard::BlackHole => "its event horizon drags at fleets and drains their CR",
+            StellarHazard::Pulsar => "its beams drain the CR of fleets caught in them",
+        }
+    }
+}
+
+/// A rough rating of how well solar arrays would do around a star.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SolarPower {
+    None,
+    Poor,
+    Adequate,
+    Strong,
+}
+
+impl SolarPower {
+    pub fn name(self) -> &'static str {
+        match self {
+            SolarPower::None => "None",
+            SolarPower::Poor => "Poor",
+            SolarPower::Adequate => "Adequate",
+            SolarPower::Strong => "Strong",
+        }
+    }
+
+    /// Whether solar arrays are worth building at this rating.
+    pub fn viable(self) -> bool {
+        self >= SolarPower::Adequate
+    }
+}
+
+/// A quarter of the sector, by which side of its centre a system lies on each axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quadrant {
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+
+impl Quadrant {
+    pub const ALL: [Quadrant; 4] = [
+        Quadrant::NorthEast,
+        Quadrant::NorthWest,
+        Quadrant::SouthEast,
+        Quadrant::SouthWest,
+    ];
+
+    /// The quadrant a hyperspace location is in, with the axes counted as north and east.
+    pub fn of(location: Position) -> Self {
+        match (location.y >= 0.0, location.x >= 0.0) {
+            (true, true) => Quadrant::NorthEast,
+            (true, false) => Quadrant::NorthWest,
+            (false, true) => Quadrant::SouthEast,
+            (false, false) => Quadrant::SouthWest,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Quadrant::NorthEast => "North-East",
+            Quadrant::NorthWest => "North-West",
+            Quadrant::SouthEast => "South-East",
+            Quadrant::SouthWest => "South-West",
+        }
+    }
+}
+
+/// How many stars a system has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Multiplicity {
+    Single,
+    Binary,
+    /// Three or more stars.
+    Trinary,
+}
+
+impl Multiplicity {
+    pub const ALL: [Multiplicity; 3] = [
+        Multiplicity::Single,
+        Multiplicity::Binary,
+        Multiplicity::Trinary,
+    ];
+
+    /// Classify a star count, with no stars at all, as in a nebula, being none of these.
+    pub fn from_count(stars: usize) -> Option<Self> {
+        match stars {
+            0 => None,
+            1 => Some(Multiplicity::Single),
+            2 => Some(Multiplicity::Binary),
+            _ => Some(Multiplicity::Trinary),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Multiplicity::Single => "Single",
+            Multiplicity::Binary => "Binary",
+            Multiplicity::Trinary => "Trinary",
+        }
+    }
+}
+
+/// Broad categories of objects, for counting and filtering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Planet,
+    Station,
+    JumpPoint,
+    /// Any other entity: probes, beacons, relays and the like.
+    Entity,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [
+        Category::Planet,
+        Category::Station,
+        Category::JumpPoint,
+        Category::Entity,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Planet => "Planets",
+            Category::Station => "Stations",
+            Category::JumpPoint => "Jump Points",
+            Category::Entity => "Other Entities",
+        }
+    }
+}
+
+/// Where a jump point sits in its system, which decides where fleets arrive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JumpPointKind {
+    /// Near the star, or orbiting a planet.
+    Inner,
+    /// Out at the edge of the system.
+    Fringe,
+}
+
+impl JumpPointKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            JumpPointKind::Inner => "Inner",
+            JumpPointKind::Fringe => "Fringe",
+        }
+    }
+}
+
+/// One-of-a-kind structures worth seeking out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unique {
+    CoronalHypershunt,
+    Cryosleeper,
+    Mothership,
+}
+
+impl Unique {
+    pub fn from_entity_type(entity_type: &str) -> Option<Self> {
+        match entity_type {
+            "coronal_tap" => Some(Unique::CoronalHypershunt),
+            "derelict_cryosleeper" => Some(Unique::Cryosleeper),
+            "derelict_mothership" => Some(Unique::Mothership),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Unique::CoronalHypershunt => "Coronal Hypershunt",
+            Unique::Cryosleeper => "Derelict Cryosleeper",
+            Unique::Mothership => "Domain-era Mothership",
+        }
+    }
+}
+
+/// How many systems each faction controls, as [`System::controlling_faction`] has it, most
+/// first and by id on a tie.  Unclaimed systems aren't counted.
+pub fn territory(systems: &[System]) -> Vec<(&str, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for faction in systems.iter().filter_map(System::controlling_faction) {
+        *counts.entry(faction).or_default() += 1;
+    }
+    let mut territory: Vec<(&str, usize)> = counts.into_iter().collect();
+    // Stable, so ties stay in id order
+    territory.sort_by_key(|t| std::cmp::Reverse(t.1));
+    territory
+}
+
+/// Whether the save marks which systems were generated, so those that aren't can be taken to be
+/// hand-authored.
+pub fn marks_procgen(systems: &[System]) -> bool {
+    systems.iter().any(|s| s.procgen)
+}
+
+/// Every unique structure in the sector, as `(system, object)` indices.
+pub fn unique_structures(systems: &[System]) -> Vec<(usize, usize, Unique)> {
+    systems
+        .iter()
+        .enumerate()
+        .flat_map(|(s, system)| {
+            system
+                .objects()
+                .filter_map(move |(o, object)| Some((s, o, object.unique()?)))
+        })
+        .collect()
+}
+
+/// Something setting a system apart from the procedurally generated run of the sector, as
+/// handcrafted and story systems tend to be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anomaly {
+    /// Far more objects than usual, with the count.
+    Crowded(usize),
+    Unique(Unique),
+}
+
+impl Anomaly {
+    pub fn describe(self) -> String {
+        match self {
+            Anomaly::Crowded(objects) => format!("Unusually crowded, with {objects} objects"),
+            Anomaly::Unique(unique) => format!("Has a {}", unique.name()),
+        }
+    }
+}
+
+/// How many standard deviations above the sector's mean object count a system must be to count
+/// as crowded.
+const CROWDED_DEVIATIONS: f64 = 2.0;
+
+/// The anomalies of each system, in the same order as `systems`.
+pub fn anomalies(systems: &[System]) -> Vec<Vec<Anomaly>> {
+    let counts: Vec<f64> = systems.iter().map(|s| s.objects.len() as f64).collect();
+    let n = counts.len().max(1) as f64;
+    let mean = counts.iter().sum::<f64>() / n;
+    let deviation = (counts.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / n).sqrt();
+    let crowded = mean + CROWDED_DEVIATIONS * deviation;
+
+    systems
+        .iter()
+        .map(|system| {
+            let objects = system.objects.len();
+            let mut found: Vec<Anomaly> = system
+                .objects
+                .iter()
+                .filter_map(|o| o.unique().map(Anomaly::Unique))
+                .collect();
+            if deviation > 0.0 && objects as f64 > crowded {
+                found.insert(0, Anomaly::Crowded(objects));
+            }
+            found
+        })
+        .collect()
+}
+
+/// The faction id the player's own holdings belong to.
+pub const PLAYER_FACTION: &str = "player";
+
+/// Display names of the factions in the base game, by id.
+const FACTION_NAMES: &[(&str, &str)] = &[
+    ("derelict", "Derelict"),
+    ("hegemony", "Hegemony"),
+    ("independent", "Independent"),
+    ("knights_of_ludd", "Knights of Ludd"),
+    ("lions_guard", "Lion's Guard"),
+    ("luddic_church", "Luddic Church"),
+    ("luddic_path", "Luddic Path"),
+    ("mercenary", "Mercenary"),
+    ("neutral", "Neutral"),
+    ("omega", "Omega"),
+    ("persean", "Persean League"),
+    ("pirates", "Pirates"),
+    ("player", "Player"),
+    ("remnant", "Remnant"),
+    ("scavengers", "Scavengers"),
+    ("sindrian_diktat", "Sindrian Diktat"),
+    ("tritachyon", "Tri-Tachyon"),
+];
+
+/// Display names of the base game's industries and structures, by id.  Defences are named in
+/// [`DEFENSES`].
+const INDUSTRY_NAMES: &[(&str, &str)] = &[
+    ("aquaculture", "Aquaculture"),
+    ("commerce", "Commerce"),
+    ("cryorevival", "Cryorevival Facility"),
+    ("cryosanctum", "Cryosanctum"),
+    ("farming", "Farming"),
+    ("fuelprod", "Fuel Production"),
+    ("heavyindustry", "Heavy Industry"),
+    ("highcommand", "High Command"),
+    ("lightindustry", "Light Industry"),
+    ("megaport", "Megaport"),
+    ("militarybase", "Military Base"),
+    ("mining", "Mining"),
+    ("orbitalworks", "Orbital Works"),
+    ("patrolhq", "Patrol HQ"),
+    ("planetaryshield", "Planetary Shield"),
+    ("population", "Population & Infrastructure"),
+    ("refining", "Refining"),
+    ("spaceport", "Spaceport"),
+    ("techmining", "Tech-Mining"),
+    ("waystation", "Waystation"),
+];
+
+/// The display name of the industry or structure with the given id, or the id itself for those
+/// the base game doesn't have.
+pub fn industry_name(id: &str) -> &str {
+    let names = INDUSTRY_NAMES.iter().map(|&(id, name)| (id, name));
+    let defenses = DEFENSES.iter().map(|&(id, name, _)| (id, name));
+    names
+        .chain(defenses)
+        .find(|(known, _)| *known == id)
+        .map_or(id, |(_, name)| name)
+}
+
+/// The display name of the faction with the given id, or the id itself for factions the base
+/// game doesn't have, such as those added by mods.
+pub fn faction_name(id: &str) -> &str {
+    FACTION_NAMES
+        .iter()
+        .find(|(known, _)| *known == id)
+        .map_or(id, |(_, name)| name)
+}
+
+/// How the owner of an object regards the player, using the game's thresholds for hostile and
+/// friendly reputations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Standing {
+    Hostile,
+    Neutral,
+    Friendly,
+}
+
+impl Standing {
+    pub fn from_relationship(relationship: f64) -> Self {
+        if relationship <= -0.5 {
+            Self::Hostile
+        } else if relationship >= 0.5 {
+            Self::Friendly
+        } else {
+            Self::Neutral
+        }
+    }
+}
+
+/// Rough planet size categories, by radius.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Size {
+    Tiny,
+    Small,
+    Medium,
+    Large,
+}
+
+impl Size {
+    pub const ALL: [Size; 4] = [Size::Tiny, Size::Small, Size::Medium, Size::Large];
+
+    pub fn from_radius(radius: f64) -> Self {
+        match radius {
+            r if r < 80.0 => Size::Tiny,
+            r if r < 130.0 => Size::Small,
+            r if r < 180.0 => Size::Medium,
+            _ => Size::Large,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Size::Tiny => "Tiny",
+            Size::Small => "Small",
+            Size::Medium => "Medium",
+            Size::Large => "Large",
+        }
+    }
+}
+
+/// The market of a colony or station.
+#[derive(Clone, Debug, Default)]
+pub struct Market {
+    /// Condition ids, such as `ore_abundant` or `hot`.
+    pub conditions: Vec<String>,
+    pub commodities: Vec<Commodity>,
+    /// Industry and structure ids, such as `spaceport` or `mining`.
+    pub industries: Vec<String>,
+    /// The colony size, which goes from 3 for an outpost up to 10.
+    pub size: Option<u8>,
+    /// How thoroughly the player has surveyed the planet.
+    pub survey: Option<SurveyLevel>,
+    /// Whether the colony is a free port, where illegal goods trade openly.
+    pub free_port: bool,
+}
+
+impl Market {
+    /// Resource deposits and their grades, from the market's conditions.
+    pub fn resources(&self) -> impl Iterator<Item = (Resource, u8)> + '_ {
+        self.conditions
+            .iter()
+            .filter_map(|c| Resource::from_condition(c))
+    }
+
+    pub fn resource(&self, resource: Resource) -> Option<u8> {
+        self.resources()
+            .filter(|r| r.0 == resource)
+            .map(|r| r.1)
+            .max()
+    }
+
+    /// The hazard rating of the market, estimated from the vanilla modifiers of its
+    /// conditions.  1.0 is the 100% baseline.
+    pub fn hazard(&self) -> f64 {
+        1.0 + self.hazards().map(|h| h.2).sum::<f64>()
+    }
+
+    /// The conditions adding to or taking from the hazard rating, with what each comes from
+    /// and its modifier, in the order the market lists them.
+    pub fn hazards(&self) -> impl Iterator<Item = (&str, HazardSource, f64)> + '_ {
+        self.conditions.iter().filter_map(|c| {
+            let &(_, source, modifier) = HAZARDS.iter().find(|h| h.0 == c)?;
+            Some((c.as_str(), source, modifier))
+        })
+    }
+
+    /// The hazard rating's modifiers summed by what they come from, leaving out any that
+    /// don't apply.
+    pub fn hazard_breakdown(&self) -> Vec<(HazardSource, f64)> {
+        HazardSource::ALL
+            .into_iter()
+            .filter_map(|source| {
+                let mut hazards = self.hazards().filter(|h| h.1 == source).peekable();
+                hazards.peek()?;
+                Some((source, hazards.map(|h| h.2).sum()))
+            })
+            .collect()
+    }
+
+    pub fn has_industry(&self, id: &str) -> bool {
+        self.industries.iter().any(|i| i == id)
+    }
+
+    /// The best port the market has built.
+    pub fn port(&self) -> Option<Port> {
+        self.industries
+            .iter()
+            .filter_map(|i| Port::from_industry(i))
+            .max()
+    }
+
+    /// The defensive structures the market has built, as `(name, strength)` pairs.
+    pub fn defenses(&self) -> impl Iterator<Item = (&'static str, u8)> + '_ {
+        self.industries.iter().filter_map(|industry| {
+            DEFENSES
+                .iter()
+                .find(|(id, ..)| id == industry)
+                .map(|&(_, name, strength)| (name, strength))
+        })
+    }
+
+    /// How well the colony is defended, or `None` where the save doesn't list its structures.
+    pub fn defense(&self) -> Option<Defense> {
+        if self.industries.is_empty() {
+            return None;
+        }
+        Some(Defense::from_strength(
+            self.defenses().map(|(_, strength)| strength).sum(),
+        ))
+    }
+
+    /// Commodities the market can't meet demand for from its own stock.
+    pub fn deficits(&self) -> impl Iterator<Item = &Commodity> {
+        self.commodities.iter().filter(|c| c.deficit() > 0.0)
+    }
+}
+
+/// The structures handling a colony's shipping, from least to most capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Port {
+    Waystation,
+    Spaceport,
+    Megaport,
+}
+
+impl Port {
+    pub fn from_industry(industry: &str) -> Option<Self> {
+        match industry {
+            "waystation" => Some(Port::Waystation),
+            "spaceport" => Some(Port::Spaceport),
+            "megaport" => Some(Port::Megaport),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Port::Waystation => "Waystation",
+            Port::Spaceport => "Spaceport",
+            Port::Megaport => "Megaport",
+        }
+    }
+}
+
+/// How much the player has learned about a planet, from sighting it to a full survey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SurveyLevel {
+    None,
+    Seen,
+    Preliminary,
+    Full,
+}
+
+impl SurveyLevel {
+    /// Match the survey level as the game writes it, such as `PRELIMINARY`.
+    fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "NONE" => Some(SurveyLevel::None),
+            "SEEN" => Some(SurveyLevel::Seen),
+            "PRELIMINARY" => Some(SurveyLevel::Preliminary),
+            "FULL" => Some(SurveyLevel::Full),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SurveyLevel::None => "Not surveyed",
+            SurveyLevel::Seen => "Seen",
+            SurveyLevel::Preliminary => "Preliminary survey",
+            SurveyLevel::Full => "Fully surveyed",
+        }
+    }
+}
+
+/// How many of the sector's planets have been fully surveyed, out of those with a survey level,
+/// as `(surveyed, total)`.  Stars have nothing to survey, so aren't counted.
+pub fn survey_progress(systems: &[System]) -> (usize, usize) {
+    all_objects(systems)
+        .filter(|(_, o)| o.kind == Kind::Planet && o.star_class().is_none())
+        .filter_map(|(_, o)| o.survey())
+        .fold((0, 0), |(surveyed, total), level| {
+            (
+                surveyed + usize::from(level == SurveyLevel::Full),
+                total + 1,
+            )
+        })
+}
+
+/// Defensive structures, by industry id, with their display names and a rough strength.
+const DEFENSES: &[(&str, &str, u8)] = &[
+    ("grounddefenses", "Ground Defenses", 1),
+    ("heavybatteries", "Heavy Batteries", 2),
+    ("patrolhq", "Patrol HQ", 1),
+    ("militarybase", "Military Base", 2),
+    ("highcommand", "High Command", 3),
+    ("orbitalstation", "Orbital Station", 1),
+    ("orbitalstation_mid", "Orbital Station", 1),
+    ("orbitalstation_high", "Orbital Station", 1),
+    ("battlestation", "Battlestation", 2),
+    ("battlestation_mid", "Battlestation", 2),
+    ("battlestation_high", "Battlestation", 2),
+    ("starfortress", "Star Fortress", 3),
+    ("starfortress_mid", "Star Fortress", 3),
+    ("starfortress_high", "Star Fortress", 3),
+];
+
+/// How hard a colony would be to raid or invade, from the strength of its defences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Defense {
+    Undefended,
+    Weak,
+    Moderate,
+    Strong,
+}
+
+impl Defense {
+    fn from_strength(strength: u8) -> Self {
+        match strength {
+            0 => Defense::Undefended,
+            1..=2 => Defense::Weak,
+            3..=4 => Defense::Moderate,
+            _ => Defense::Strong,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Defense::Undefended => "Undefended",
+            Defense::Weak => "Weak",
+            Defense::Moderate => "Moderate",
+            Defense::Strong => "Strong",
+        }
+    }
+
+    /// Whether a colony is a soft target for raids.
+    pub fn weak(self) -> bool {
+        self <= Defense::Weak
+    }
+}
+
+/// Structures built at stable locations, which benefit whichever faction owns them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Infrastructure {
+    CommRelay,
+    NavBuoy,
+    SensorArray,
+}
+
+impl Infrastructure {
+    pub const ALL: [Infrastructure; 3] = [
+        Infrastructure::CommRelay,
+        Infrastructure::NavBuoy,
+        Infrastructure::SensorArray,
+    ];
+
+    /// Match a custom entity type, Domain-era or makeshift.
+    pub fn from_entity_type(entity_type: &str) -> Option<Self> {
+        match entity_type.trim_end_matches("_makeshift") {
+            "comm_relay" => Some(Infrastructure::CommRelay),
+            "nav_buoy" => Some(Infrastructure::NavBuoy),
+            "sensor_array" => Some(Infrastructure::SensorArray),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Infrastructure::CommRelay => "Comm Relay",
+            Infrastructure::NavBuoy => "Nav Buoy",
+            Infrastructure::SensorArray => "Sensor Array",
+        }
+    }
+}
+
+/// How dangerous a system's Remnant presence is, as its warning beacon has it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Danger {
+    Low,
+    Medium,
+    High,
+}
+
+impl Danger {
+    pub const ALL: [Danger; 3] = [Danger::Low, Danger::Medium, Danger::High];
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "beacon_low" => Some(Danger::Low),
+            "beacon_medium" => Some(Danger::Medium),
+            "beacon_high" => Some(Danger::High),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Danger::Low => "Low",
+            Danger::Medium => "Medium",
+            Danger::High => "High",
+        }
+    }
+}
+
+/// A region of terrain, approximated as a disc or ring around its centre.
+#[derive(Debug, Clone, Copy)]
+pub struct Terrain {
+    pub kind: TerrainKind,
+    pub center: Position,
+    /// The inner edge of a ring or belt, or zero for terrain covering a disc.
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerrainKind {
+    Nebula,
+    AsteroidBelt,
+    Ring,
+}
+
+/// A cell of the hyperspace terrain's grid with a storm brewing, raging or dying down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StormCell {
+    /// The middle of the cell, in hyperspace.
+    pub center: Position,
+    /// How wide the cell is, on each side.
+    pub size: f64,
+    /// Whether the storm's at its height and striking, rather than gathering or waning.
+    pub raging: bool,
+}
+
+impl StormCell {
+    /// How far `pos` is from the edge of the cell, or zero within it.
+    pub fn distance(&self, pos: &Position) -> f64 {
+        let outside = |offset: f64| (offset.abs() - self.size / 2.0).max(0.0);
+        outside(pos.x - self.center.x).hypot(outside(pos.y - self.center.y))
+    }
+}
+
+/// How wide each tile of the hyperspace terrain's grid is, as the game sets it.
+pub const HYPERSPACE_TILE_SIZE: f64 = 200.0;
+
+/// The plugin the game's hyperspace clouds and storms are serialized with.
+const HYPERSPACE_TERRAIN: &str =
+    "com.fs.starfarer.api.impl.campaign.terrain.HyperspaceTerrainPlugin";
+
+impl TerrainKind {
+    /// Match the terrain plugin class a terrain entity is serialized with.
+    fn from_plugin(class: &str) -> Option<Self> {
+        match class.rsplit('.').next()? {
+            "NebulaTerrainPlugin" => Some(Self::Nebula),
+            "AsteroidBeltTerrainPlugin" => Some(Self::AsteroidBelt),
+            "RingSystemTerrainPlugin" => Some(Self::Ring),
+            _ => None,
+        }
+    }
+}
+
+/// What a planet condition's contribution to the hazard rating comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HazardSource {
+    Atmosphere,
+    Temperature,
+    Climate,
+    Biosphere,
+    Radiation,
+    Gravity,
+    Geology,
+    Pollution,
+    Light,
+}
+
+impl HazardSource {
+    pub const ALL: [HazardSource; 9] = [
+        HazardSource::Atmosphere,
+        HazardSource::Temperature,
+        HazardSource::Climate,
+        HazardSource::Biosphere,
+        HazardSource::Radiation,
+        HazardSource::Gravity,
+        HazardSource::Geology,
+        HazardSource::Pollution,
+        HazardSource::Light,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HazardSource::Atmosphere => "Atmosphere",
+            HazardSource::Temperature => "Temperature",
+            HazardSource::Climate => "Climate",
+            HazardSource::Biosphere => "Biosphere",
+            HazardSource::Radiation => "Radiation",
+            HazardSource::Gravity => "Gravity",
+            HazardSource::Geology => "Geology",
+            HazardSource::Pollution => "Pollution",
+            HazardSource::Light => "Light",
+        }
+    }
+}
+
+/// Hazard rating modifiers of planet conditions, and what each comes from.
+const HAZARDS: &[(&str, HazardSource, f64)] = &[
+    ("habitable", HazardSource::Atmosphere, -0.25),
+    ("mild_climate", HazardSource::Climate, -0.25),
+    ("cold", HazardSource::Temperature, 0.25),
+    ("very_cold", HazardSource::Temperature, 0.25),
+    ("hot", HazardSource::Temperature, 0.25),
+    ("very_hot", HazardSource::Temperature, 0.25),
+    ("extreme_weather", HazardSource::Climate, 0.25),
+    ("inimical_biosphere", HazardSource::Biosphere, 0.25),
+    ("toxic_atmosphere", HazardSource::Atmosphere, 0.25),
+    ("dense_atmosphere", HazardSource::Atmosphere, 0.25),
+    ("thin_atmosphere", HazardSource::Atmosphere, 0.25),
+    ("no_atmosphere", HazardSource::Atmosphere, 0.5),
+    ("irradiated", HazardSource::Radiation, 0.5),
+    ("low_gravity", HazardSource::Gravity, 0.25),
+    ("high_gravity", HazardSource::Gravity, 0.25),
+    ("tectonic_activity", HazardSource::Geology, 0.25),
+    ("extreme_tectonic_activity", HazardSource::Geology, 0.5),
+    ("meteor_impacts", HazardSource::Geology, 0.5),
+    ("pollution", HazardSource::Pollution, 0.25),
+    ("poor_light", HazardSource::Light, 0.25),
+    ("dark", HazardSource::Light, 0.5),
+];
+
+/// A resource deposit, provided by a planet condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Resource {
+    Ore,
+    RareOre,
+    Volatiles,
+    Organics,
+    Farmland,
+}
+
+impl Resource {
+    pub const ALL: [Resource; 5] = [
+        Resource::Ore,
+        Resource::RareOre,
+        Resource::Volatiles,
+        Resource::Organics,
+        Resource::Farmland,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Resource::Ore => "Ore",
+            Resource::RareOre => "Rare Ore",
+            Resource::Volatiles => "Volatiles",
+            Resource::Organics => "Organics",
+            Resource::Farmland => "Farmland",
+        }
+    }
+
+    /// The condition id prefix for this resource, and its grades from poorest to richest.
+    fn grades(self) -> (&'static str, &'static [&'static str]) {
+        const ORE: &[&str] = &["sparse", "moderate", "abundant", "rich", "ultrarich"];
+        match self {
+            Resource::Ore => ("ore_", ORE),
+            Resource::RareOre => ("rare_ore_", ORE),
+            Resource::Volatiles => ("volatiles_", &["trace", "diffuse", "abundant", "plentiful"]),
+            Resource::Organics => ("organics_", &["trace", "common", "abundant", "plentiful"]),
+            Resource::Farmland => ("farmland_", &["poor", "adequate", "rich", "bountiful"]),
+        }
+    }
+
+    /// The number of grades this resource comes in.
+    pub fn max_grade(self) -> u8 {
+        self.grades().1.len() as u8
+    }
+
+    /// The name of a grade, where 1 is the poorest.
+    pub fn grade_name(self, grade: u8) -> &'static str {
+        let grades = self.grades().1;
+        grades[(grade.max(1) as usize - 1).min(grades.len() - 1)]
+    }
+
+    /// Whether a salvage drop group or entity type id like `volatiles_cache` names the
+    /// commodity this resource yields.
+    fn yielded_by(self, id: &str) -> bool {
+        let words: Vec<&str> = id.split('_').collect();
+        let ore = |i: usize| words[i] == "ore" && (i == 0 || words[i - 1] != "rare");
+        match self {
+            Resource::Ore => (0..words.len()).any(ore),
+            Resource::RareOre => words.windows(2).any(|w| w == ["rare", "ore"]),
+            Resource::Volatiles => words.contains(&"volatiles"),
+            Resource::Organics => words.contains(&"organics"),
+            Resource::Farmland => words.contains(&"food"),
+        }
+    }
+
+    /// The resource and grade provided by a condition id like `ore_abundant`.
+    pub fn from_condition(id: &str) -> Option<(Resource, u8)> {
+        Resource::ALL.into_iter().find_map(|resource| {
+            let (prefix, grades) = resource.grades();
+            let grade = id.strip_prefix(prefix)?;
+            let index = grades.iter().position(|&g| g == grade)?;
+            Some((resource, index as u8 + 1))
+        })
+    }
+}
+
+/// Whether a resource keeps flowing, or can be had just the once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Supply {
+    /// A deposit from a planet condition, mined for as long as there's a colony to mine it.
+    Sustained,
+    /// A cache left for salvagers, gone once it's picked over.
+    OneTime,
+}
+
+impl Supply {
+    pub const ALL: [Supply; 2] = [Supply::Sustained, Supply::OneTime];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Supply::Sustained => "Sustained",
+            Supply::OneTime => "One-time",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Commodity {
+    pub id: String,
+    pub available: f64,
+    pub demand: f64,
+}
+
+impl Commodity {
+    /// How many units of demand exceed what's available.
+    pub fn deficit(&self) -> f64 {
+        (self.demand - self.available).max(0.0)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct System {
+    pub name: String,
+    pub lc_name: String,
+    /// The id the game and mods refer to this system by, such as `corvus`.
+    pub internal_id: Option<String>,
+    /// Where the system sits in hyperspace.
+    pub location: Option<Position>,
+    pub objects: Vec<Object>,
+    pub mission: bool,
+    pub terrain: Vec<Terrain>,
+    pub fleets: Vec<Fleet>,
+    /// Whether the player has ever entered the system, going by the time of their last visit.
+    pub entered: bool,
+    /// Whether the game generated the system when the sector was created, rather than it being
+    /// written by hand like the core worlds and story systems.  Only saves that mark any system
+    /// as generated can tell the two apart: see [`marks_procgen`].
+    pub procgen: bool,
+    /// The name of the constellation the system belongs to, such as "Hydra Constellation",
+    /// where the save says.
+    pub constellation: Option<String>,
+}
+
+/// A fleet in a system.
+#[derive(Clone, Debug, Default)]
+pub struct Fleet {
+    /// The id of the faction the fleet belongs to.
+    pub faction: Option<String>,
+    /// Whether this is the player's own fleet.
+    pub player: bool,
+    /// Whether this is a station, such as a Remnant battlestation, rather than a fleet that moves.
+    pub station: bool,
+}
+
+impl Fleet {
+    pub fn remnant(&self) -> bool {
+        self.faction.as_deref() == Some(REMNANT_FACTION)
+    }
+}
+
+/// The faction id of the hostile AI fleets guarding remnant systems.
+pub const REMNANT_FACTION: &str = "remnant";
+
+/// The custom entity type of the beacons warning of Remnant systems.
+pub const BEACON_ENTITY: &str = "warning_beacon";
+
+/// The custom entity type of gates.
+pub const GATE_ENTITY: &str = "inactive_gate";
+
+/// The memory flag set on a gate once the player has scanned it, and can travel through it.
+const GATE_SCANNED: &str = "$gateScanned";
+
+/// Whether a gate is part of the player's network yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateState {
+    /// Scanned, so the player can jump through it.
+    Active,
+    /// Yet to be scanned with a Janus Device before it can be used.
+    Inactive,
+}
+
+impl GateState {
+    pub fn name(self) -> &'static str {
+        match self {
+            GateState::Active => "Active",
+            GateState::Inactive => "Inactive, needs activating",
+        }
+    }
+}
+
+/// The custom entity type of stable locations with nothing built at them.
+pub const STABLE_LOCATION_ENTITY: &str = "stable_location";
+
+impl System {
+    /// Whether the system has planets but no colonies, markets or anything else owned by a
+    /// faction: unclaimed territory.
+    pub fn uncolonized(&self) -> bool {
+        self.objects.iter().any(|o| o.kind == Kind::Planet)
+            && self
+                .objects
+                .iter()
+                .all(|o| o.market.is_none() && o.faction.is_none())
+    }
+
+    /// Whether the system has an asteroid belt to mine.
+    pub fn has_asteroid_belt(&self) -> bool {
+        self.terrain
+            .iter()
+            .any(|t| t.kind == TerrainKind::AsteroidBelt)
+    }
+
+    /// How far round its orbit the object at `index` is, in degrees anticlockwise from east: as
+    /// the save has it, or else worked out from where it is relative to its parent.
+    pub fn orbital_angle(&self, index: usize) -> Option<f64> {
+        let object = self.objects.get(index)?;
+        if let Some(angle) = object.orbit.and_then(|o| o.angle) {
+            return Some(angle.rem_euclid(360.0));
+        }
+        let parent = &self.objects[object.parent?];
+        let (x, y) = (object.pos.x - parent.pos.x, object.pos.y - parent.pos.y);
+        Some(y.atan2(x).to_degrees().rem_euclid(360.0))
+    }
+
+    /// Every mission targeting something in the system.
+    pub fn missions(&self) -> impl Iterator<Item = &Mission> + '_ {
+        self.objects.iter().flat_map(|o| &o.missions)
+    }
+
+    /// The factions offering missions in the system, by id, in the order they're first met.
+    pub fn mission_factions(&self) -> Vec<&str> {
+        let mut factions = vec![];
+        for faction in self.missions().filter_map(|m| m.faction.as_deref()) {
+            if !factions.contains(&faction) {
+                factions.push(faction);
+            }
+        }
+        factions
+    }
+
+    pub fn has_gate(&self) -> bool {
+        self.objects.iter().any(|o| o.is_gate())
+    }
+
+    /// Domain-era sensor arrays that are still working for someone.
+    pub fn domain_sensor_arrays(&self) -> impl Iterator<Item = &Object> {
+        self.objects.iter().filter(|o| {
+            o.infrastructure() == Some(Infrastructure::SensorArray)
+                && o.domain_era()
+                && o.operational()
+        })
+    }
+
+    /// The kinds of infrastructure the player has yet to own in a system where they have a
+    /// colony, which gains from them.  Elsewhere, nothing is missing.
+    pub fn missing_infrastructure(&self) -> Vec<Infrastructure> {
+        let player = |o: &Object| o.faction.as_deref() == Some(PLAYER_FACTION);
+        if !self.objects.iter().any(|o| player(o) && o.market.is_some()) {
+            return vec![];
+        }
+        Infrastructure::ALL
+            .into_iter()
+            .filter(|&kind| {
+                !self
+                    .objects
+                    .iter()
+                    .any(|o| player(o) && o.infrastructure() == Some(kind))
+            })
+            .collect()
+    }
+
+    /// The number of stable locations in the system, whether or not they're built on.
+    pub fn stable_locations(&self) -> usize {
+        self.objects
+            .iter()
+            .filter(|o| o.is_stable_location() || o.occupies_stable_location())
+            .count()
+    }
+
+    /// The number of stable locations still free to build a relay, buoy or array at.
+    pub fn free_stable_locations(&self) -> usize {
+        self.objects
+            .iter()
+            .filter(|o| o.is_stable_location())
+            .count()
+    }
+
+    pub fn remnant_fleets(&self) -> usize {
+        self.fleets.iter().filter(|f| f.remnant()).count()
+    }
+
+    pub fn remnant_stations(&self) -> usize {
+        self.fleets
+            .iter()
+            .filter(|f| f.remnant() && f.station)
+            .count()
+    }
+
+    /// The danger of the system's Remnant presence, from the most dire of its warning beacons.
+    pub fn danger(&self) -> Option<Danger> {
+        self.objects.iter().filter_map(Object::beacon_danger).max()
+    }
+
+    /// Whether the player's fleet was in this system when the save was made.
+    pub fn has_player(&self) -> bool {
+        self.fleets.iter().any(|f| f.player)
+    }
+
+    /// How far the system is from the nearest of `storms`, or zero if it's within one, where
+    /// its location's known.
+    pub fn storm_distance(&self, storms: &[StormCell]) -> Option<f64> {
+        let location = self.location?;
+        storms
+            .iter()
+            .map(|storm| storm.distance(&location))
+            .min_by(f64::total_cmp)
+    }
+
+    /// Whether the player has been to the system, or is there now.
+    pub fn visited(&self) -> bool {
+        self.entered || self.has_player()
+    }
+
+    /// Whether the object at `index` is an inner or fringe jump point.  Fringe jump points are
+    /// named as such by the game; failing that, the furthest of several from the centre is.
+    pub fn jump_point(&self, index: usize) -> Option<JumpPointKind> {
+        let is_jump_point = |o: &Object| o.category() == Category::JumpPoint;
+        let object = self.objects.get(index).filter(|o| is_jump_point(o))?;
+        let named = |o: &Object| o.name.to_lowercase().contains("fringe");
+        if named(object) {
+            return Some(JumpPointKind::Fringe);
+        }
+
+        let jump_points: Vec<(usize, &Object)> =
+            self.objects().filter(|(_, o)| is_jump_point(o)).collect();
+        if jump_points.len() < 2 || jump_points.iter().any(|(_, o)| named(o)) {
+            return Some(JumpPointKind::Inner);
+        }
+        let distance = |o: &Object| o.pos.x.hypot(o.pos.y);
+        let furthest = jump_points
+            .iter()
+            .max_by(|a, b| distance(a.1).total_cmp(&distance(b.1)))
+            .map(|&(i, _)| i);
+        Some(if furthest == Some(index) {
+            JumpPointKind::Fringe
+        } else {
+            JumpPointKind::Inner
+        })
+    }
+
+    /// The system's primary star: the first star not orbiting anything.
+    pub fn star(&self) -> Option<(&Object, StarClass)> {
+        self.objects
+            .iter()
+            .filter(|o| o.parent.is_none())
+            .find_map(|o| Some((o, o.star_class()?)))
+    }
+
+    /// The number of stars in the system, black holes and neutron stars included.
+    pub fn star_count(&self) -> usize {
+        self.objects
+            .iter()
+            .filter(|o| o.star_class().is_some())
+            .count()
+    }
+
+    /// The hazardous stars of the system, each kind once.
+    pub fn stellar_hazards(&self) -> Vec<StellarHazard> {
+        let mut hazards: Vec<StellarHazard> = self
+            .objects
+            .iter()
+            .filter_map(|o| o.star_class()?.hazard())
+            .collect();
+        hazards.sort_unstable();
+        hazards.dedup();
+        hazards
+    }
+
+    pub fn multiplicity(&self) -> Option<Multiplicity> {
+        Multiplicity::from_count(self.star_count())
+    }
+
+    /// Whether any colony in the system is a free port.
+    pub fn has_free_port(&self) -> bool {
+        self.objects
+            .iter()
+            .any(|o| o.market.as_ref().is_some_and(|m| m.free_port))
+    }
+
+    /// The best port of any colony in the system.
+    pub fn port(&self) -> Option<Port> {
+        self.objects
+            .iter()
+            .filter_map(|o| o.market.as_ref()?.port())
+            .max()
+    }
+
+    /// The solar power rating of the system's primary star.
+    pub fn solar_power(&self) -> Option<SolarPower> {
+        self.star().map(|(_, class)| class.solar_power())
+    }
+
+    /// The objects in the system along with their index, as used by [`Object::parent`].
+    pub fn objects(&self) -> impl Iterator<Item = (usize, &Object)> {
+        self.objects.iter().enumerate()
+    }
+
+    /// What tells the object at `index` apart from every other in the system, and finds it
+    /// again when the save's reloaded: its [`Object::trail_key`], and how many objects before
+    /// it share that, as same-named stable locations and relays without an id might.
+    pub fn object_key(&self, index: usize) -> (&str, usize) {
+        let key = self.objects[index].trail_key();
+        let before = self.objects[..index].iter();
+        (key, before.filter(|o| o.trail_key() == key).count())
+    }
+
+    /// The index of the object with the [`System::object_key`] `key` and `nth`.
+    pub fn find_object(&self, key: &str, nth: usize) -> Option<usize> {
+        self.objects()
+            .filter(|(_, o)| o.trail_key() == key)
+            .nth(nth)
+            .map(|(index, _)| index)
+    }
+
+    /// The quarter of the sector the system is in, going by its hyperspace location.
+    pub fn quadrant(&self) -> Option<Quadrant> {
+        self.location.map(Quadrant::of)
+    }
+
+    /// The region of the sector the system is in: its constellation, or otherwise its
+    /// quadrant.
+    pub fn region(&self) -> Option<&str> {
+        self.constellation
+            .as_deref()
+            .or_else(|| Some(self.quadrant()?.name()))
+    }
+
+    /// The faction owning the most markets in the system, preferring the first encountered on a
+    /// tie.
+    pub fn controlling_faction(&self) -> Option<&str> {
+        let mut counts: Vec<(&str, usize)> = vec![];
+        for object in self.objects.iter().filter(|o| o.market.is_some()) {
+            let Some(faction) = object.faction.as_deref() else { continue };
+            match counts.iter_mut().find(|c| c.0 == faction) {
+                Some(count) => count.1 += 1,
+                None => counts.push((faction, 1)),
+            }
+        }
+
+        counts.into_iter().rev().max_by_key(|c| c.1).map(|c| c.0)
+    }
+
+    /// The system's most notable resource: the one found at the highest grade relative to the
+    /// best it comes in, preferring the earlier in [`Resource::ALL`] on a tie.
+    pub fn headline_resource(&self) -> Option<(Resource, u8)> {
+        self.headline_resources().into_iter().next()
+    }
+
+    /// Every resource in the system, ordered as for [`System::headline_resource`].
+    pub fn headline_resources(&self) -> Vec<(Resource, u8)> {
+        let mut resources: Vec<(Resource, u8)> = Resource::ALL
+            .into_iter()
+            .filter_map(|resource| Some((resource, self.resource(resource)?)))
+            .collect();
+        let quality = |&(resource, grade): &(Resource, u8)| {
+            f64::from(grade) / f64::from(resource.max_grade())
+        };
+        // Stable, so ties stay in their order in `Resource::ALL`
+        resources.sort_by(|a, b| quality(b).total_cmp(&quality(a)));
+        resources
+    }
+
+    /// The best grade of `resource` found on any object in the system.
+    pub fn resource(&self, resource: Resource) -> Option<u8> {
+        self.objects
+            .iter()
+            .filter_map(|o| o.market.as_ref()?.resource(resource))
+            .max()
+    }
+
+    /// Whether anything in the system supplies `resource` in the given way.
+    pub fn supplies(&self, resource: Resource, supply: Supply) -> bool {
+        match supply {
+            Supply::Sustained => self.resource(resource).is_some(),
+            Supply::OneTime => self
+                .objects
+                .iter()
+                .any(|o| o.one_time_resources().any(|r| r == resource)),
+        }
+    }
+}
+
+/// Every object in the sector, alongside the system it belongs to.
+pub fn all_objects(systems: &[System]) -> impl Iterator<Item = (&System, &Object)> {
+    systems
+        .iter()
+        .flat_map(|system| system.objects.iter().map(move |object| (system, object)))
+}
+
+/// The most hazardous a planet can be while still counting as a home-world candidate.
+pub const HOME_WORLD_MAX_HAZARD: f64 = 1.25;
+
+/// Habitable, terran-like planets with low hazard across the sector, as `(system, object,
+/// hazard)` indices ordered from least to most hazardous.
+pub fn home_world_candidates(systems: &[System]) -> Vec<(usize, usize, f64)> {
+    let mut candidates: Vec<_> = systems
+        .iter()
+        .enumerate()
+        .flat_map(|(s, system)| {
+            system.objects().filter_map(move |(o, object)| {
+                let market = object.market.as_ref().filter(|_| object.terran_like())?;
+                let hazard = market.hazard();
+                (market.conditions.iter().any(|c| c == "habitable")
+                    && hazard <= HOME_WORLD_MAX_HAZARD)
+                    .then_some((s, o, hazard))
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.2.total_cmp(&b.2));
+    candidates
+}
+
+/// Systems the player has never been to, as indices with their hyperspace distance from the
+/// player's current system, nearest first.  Without a position for either, systems come last in
+/// their original order.
+pub fn unvisited_systems(systems: &[System]) -> Vec<(usize, Option<f64>)> {
+    let origin = systems
+        .iter()
+        .find(|s| s.has_player())
+        .and_then(|s| s.location);
+    let mut unvisited: Vec<_> = systems
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| !s.visited())
+        .map(|(i, s)| {
+            let distance = s.location.zip(origin).map(|(a, b)| a.distance(&b));
+            (i, distance)
+        })
+        .collect();
+    unvisited.sort_by(|a, b| match (a.1, b.1) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (a, b) => b.is_some().cmp(&a.is_some()),
+    });
+    unvisited
+}
+
+/// A rough rating of a planet as a colony site: the richness of each of its resources as a
+/// share of the best grade it comes in, summed, scaled to percent and divided by the hazard
+/// rating.
+pub fn colony_score(market: &Market) -> f64 {
+    let richness: f64 = market
+        .resources()
+        .map(|(resource, grade)| f64::from(grade) / f64::from(resource.max_grade()))
+        .sum();
+    100.0 * richness / market.hazard()
+}
+
+/// Planets nobody else has colonised yet, as `(system, object, score)` indices ordered from
+/// the best [`colony_score`] down.
+pub fn colony_candidates(systems: &[System]) -> Vec<(usize, usize, f64)> {
+    let mut candidates: Vec<_> = systems
+        .iter()
+        .enumerate()
+        .flat_map(|(s, system)| {
+            system.objects().filter_map(move |(o, object)| {
+                let market = object
+                    .market
+                    .as_ref()
+                    .filter(|_| object.kind == Kind::Planet)?;
+                let unclaimed = object
+                    .faction
+                    .as_deref()
+                    .is_none_or(|f| f == PLAYER_FACTION);
+                unclaimed.then(|| (s, o, colony_score(market)))
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2));
+    candidates
+}
+
+/// Links from each system with a player colony to the `per_colony` nearest systems with
+/// another faction's market, as `(from, to)` indices, for picturing supply lines.
+pub fn trade_links(systems: &[System], per_colony: usize) -> Vec<(usize, usize)> {
+    let has_market = |system: &System, player: bool| {
+        system.objects.iter().any(|o| {
+            o.market.is_some()
+                && o.faction.is_some()
+                && (o.faction.as_deref() == Some(PLAYER_FACTION)) == player
+        })
+    };
+
+    let mut links = vec![];
+    for (from, colony) in systems.iter().enumerate() {
+        let Some(origin) = colony.location.filter(|_| has_market(colony, true)) else { continue };
+        let mut markets: Vec<(usize, f64)> = systems
+            .iter()
+            .enumerate()
+            .filter(|&(to, system)| to != from && has_market(system, false))
+            .filter_map(|(to, system)| Some((to, system.location?.distance(&origin))))
+            .collect();
+        markets.sort_by(|a, b| a.1.total_cmp(&b.1));
+        links.extend(
+            markets
+                .into_iter()
+                .take(per_colony)
+                .map(|(to, _)| (from, to)),
+        );
+    }
+    links
+}
+
+/// Hyperspace units per light-year, matching the game's own distance readouts.
+pub const UNITS_PER_LIGHT_YEAR: f64 = 2000.0;
+
+/// The systems making up the vanilla core worlds.
+pub const CORE_SYSTEMS: &[&str] = &[
+    "Al Gebbar",
+    "Arcadia",
+    "Askonia",
+    "Aztlan",
+    "Canaan",
+    "Corvus",
+    "Duzahk",
+    "Eos Exodus",
+    "Galatia",
+    "Hybrasil",
+    "Isirah",
+    "Kumari Kandam",
+    "Magec",
+    "Mayasura",
+    "Naraka",
+    "Penelope's Star",
+    "Samarra",
+    "Thule",
+    "Tyle",
+    "Valhalla",
+    "Westernesse",
+    "Yma",
+    "Zagan",
+];
+
+/// The hyperspace distance from `system` to the nearest core world system in `systems`.
+pub fn core_distance(systems: &[System], system: &System) -> Option<f64> {
+    let location = system.location?;
+    systems
+        .iter()
+        .filter(|s| CORE_SYSTEMS.contains(&s.name.as_str()))
+        .filter_map(|s| Some(s.location?.distance(&location)))
+        .reduce(f64::min)
+}
+
+/// A rough estimate of the accessibility lost by a colony `distance` units from the core.
+///
+/// The save doesn't record this, so it's modelled as 1% per light-year beyond the first ten,
+/// up to a maximum of 50%.
+pub fn estimated_accessibility_penalty(distance: f64) -> f64 {
+    ((distance / UNITS_PER_LIGHT_YEAR - 10.0) / 100.0).clamp(0.0, 0.5)
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    Io(std::io::Error),
+    Xml(roxmltree::Error),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read save: {e}"),
+            Self::Xml(e) => write!(f, "Failed to parse save: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Xml(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<roxmltree::Error> for ParseError {
+    fn from(e: roxmltree::Error) -> Self {
+        Self::Xml(e)
+    }
+}
+
+/// Game versions whose saves are known to parse correctly, as `(major, minor)` pairs.
+pub const TESTED_VERSIONS: std::ops::RangeInclusive<(u32, u32)> = (0, 95)..=(0, 97);
+
+/// Save metadata from the `descriptor.xml` next to a campaign save.
+#[derive(Clone, Debug, Default)]
+pub struct Descriptor {
+    /// The version of the game that wrote the save, such as `0.96a-RC10`.
+    pub game_version: Option<String>,
+    pub character_name: Option<String>,
+    /// When the save was made, as written by the game.
+    pub save_date: Option<String>,
+    pub credits: Option<f64>,
+    /// The seed the sector was generated from, as shown on the new game screen.
+    pub seed: Option<String>,
+}
+
+impl Descriptor {
+    /// Whether the save was written by a game version outside [`TESTED_VERSIONS`].
+    pub fn untested_version(&self) -> bool {
+        self.game_version
+            .as_deref()
+            .and_then(parse_game_version)
+            .is_some_and(|v| !TESTED_VERSIONS.contains(&v))
+    }
+}
+
+/// Measures of how far a campaign has progressed, for comparing saves over time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Progress {
+    pub save_date: Option<String>,
+    pub credits: Option<f64>,
+    /// Markets owned by the player.
+    pub colonies: usize,
+    /// Systems with every object discovered.
+    pub explored_systems: usize,
+    /// Where each object was, by system name and then [`Object::trail_key`].
+    pub positions: BTreeMap<String, BTreeMap<String, Position>>,
+}
+
+impl Progress {
+    pub fn of(descriptor: &Descriptor, systems: &[System]) -> Self {
+        Self {
+            save_date: descriptor.save_date.clone(),
+            credits: descriptor.credits,
+            colonies: all_objects(systems)
+                .filter(|(_, o)| o.market.is_some())
+                .filter(|(_, o)| o.faction.as_deref() == Some(PLAYER_FACTION))
+                .count(),
+            explored_systems: systems
+                .iter()
+                .filter(|s| !s.objects.is_empty() && s.objects.iter().all(|o| o.discovered))
+                .count(),
+            positions: systems
+                .iter()
+                .map(|s| {
+                    let objects = s.objects.iter();
+                    let positions = objects.map(|o| (o.trail_key().to_string(), o.pos));
+                    (s.name.clone(), positions.collect())
+                })
+                .collect(),
+        }
+    }
+
+    /// Load the save at `path` and measure it, along with its descriptor if there is one.
+    pub fn load(path: &Path) -> Result<Self, ParseError> {
+        let descriptor = load_descriptor(path).unwrap_or_default();
+        Ok(Self::of(&descriptor, &load_sector(path)?.systems))
+    }
+}
+
+/// The positions of each object in `system` across `saves`, in the order given, keyed by
+/// [`Object::trail_key`].  Objects only found in one of the saves have no trail to draw, so
+/// are left out.
+pub fn trails<'a>(
+    saves: impl IntoIterator<Item = &'a Progress>,
+    system: &str,
+) -> BTreeMap<String, Vec<Position>> {
+    let mut trails: BTreeMap<String, Vec<Position>> = BTreeMap::new();
+    for positions in saves.into_iter().filter_map(|p| p.positions.get(system)) {
+        for (key, &position) in positions {
+            trails.entry(key.clone()).or_default().push(position);
+        }
+    }
+    trails.retain(|_, trail| trail.len() > 1);
+    trails
+}
+
+/// How an object differs from one load of a save to the next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Appeared,
+    Disappeared,
+    /// Still there, but its faction, colony, discovery or missions aren't what they were.
+    Changed,
+}
+
+impl Change {
+    pub fn name(self) -> &'static str {
+        match self {
+            Change::Appeared => "Appeared",
+            Change::Disappeared => "Disappeared",
+            Change::Changed => "Changed",
+        }
+    }
+}
+
+/// An object that [`changes`] found had appeared, disappeared or changed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ObjectChange {
+    /// The object's [`Object::trail_key`].
+    pub key: String,
+    pub name: String,
+    pub change: Change,
+    /// Where the object is now, or was last seen if it's gone.
+    pub pos: Position,
+}
+
+/// What's different about each system's objects in `after` compared to `before`, by system
+/// name, leaving out systems with no differences.  Objects are matched up by
+/// [`Object::trail_key`], and moving along their orbits doesn't count as a change.
+pub fn changes(before: &[System], after: &[System]) -> BTreeMap<String, Vec<ObjectChange>> {
+    let earlier: BTreeMap<&str, &System> = before.iter().map(|s| (s.name.as_str(), s)).collect();
+    let mut changes = BTreeMap::new();
+    for system in after {
+        let old: BTreeMap<&str, &Object> = earlier
+            .get(system.name.as_str())
+            .map(|s| s.objects.iter().map(|o| (o.trail_key(), o)).collect())
+            .unwrap_or_default();
+        let new: BTreeMap<&str, &Object> =
+            system.objects.iter().map(|o| (o.trail_key(), o)).collect();
+
+        let change = |object: &Object, change| ObjectChange {
+            key: object.trail_key().to_string(),
+            name: object.name.clone(),
+            change,
+            pos: object.pos,
+        };
+        let mut found: Vec<ObjectChange> = new
+            .iter()
+            .filter_map(|(key, object)| match old.get(key) {
+                None => Some(change(object, Change::Appeared)),
+                Some(was) if !same_state(was, object) => Some(change(object, Change::Changed)),
+                Some(_) => None,
+            })
+            .collect();
+        found.extend(
+            old.iter()
+                .filter(|(key, _)| !new.contains_key(*key))
+                .map(|(_, object)| change(object, Change::Disappeared)),
+        );
+        if !found.is_empty() {
+            changes.insert(system.name.clone(), found);
+        }
+    }
+    changes
+}
+
+/// Whether the object is as it was, ignoring where it's got to in its orbit.
+fn same_state(a: &Object, b: &Object) -> bool {
+    let colony = |o: &Object| {
+        o.market.as_ref().map(|m| {
+            (
+                m.size,
+                m.free_port,
+                m.industries.clone(),
+                m.conditions.clone(),
+            )
+        })
+    };
+    a.name == b.name
+        && a.faction == b.faction
+        && a.discovered == b.discovered
+        && a.scanned == b.scanned
+        && a.missions == b.missions
+        && a.salvage == b.salvage
+        && a.tags == b.tags
+        && colony(a) == colony(b)
+}
+
+/// Read the descriptor accompanying the campaign save at `save`.
+pub fn load_descriptor(save: &Path) -> Result<Descriptor, ParseError> {
+    let xml = std::fs::read_to_string(save.with_file_name("descriptor.xml"))?;
+    let doc = roxmltree::Document::parse(&xml)?;
+    let root = doc.root_element();
+
+    Ok(Descriptor {
+        game_version: child_text(&root, "gameVersion").map(str::to_string),
+        character_name: child_text(&root, "characterName").map(str::to_string),
+        save_date: child_text(&root, "saveDate").map(str::to_string),
+        credits: child_text(&root, "credits").and_then(parse_number),
+        seed: child_text(&root, "seedString").map(str::to_string),
+    })
+}
+
+/// A campaign save found in a saves directory, described but not yet parsed.
+#[derive(Clone, Debug)]
+pub struct Campaign {
+    /// The campaign save itself, `campaign.xml` or a compressed copy of it.
+    pub path: PathBuf,
+    pub descriptor: Descriptor,
+}
+
+impl Campaign {
+    /// A name to list the campaign under: its character and save date where known, or else
+    /// the directory it's saved in.
+    pub fn name(&self) -> String {
+        let directory = || {
+            self.path.parent().and_then(Path::file_name).map_or_else(
+                || self.path.display().to_string(),
+                |d| d.to_string_lossy().into(),
+            )
+        };
+        match (&self.descriptor.character_name, &self.descriptor.save_date) {
+            (Some(name), Some(date)) => format!("{name} ({date})"),
+            (Some(name), None) => name.clone(),
+            _ => directory(),
+        }
+    }
+}
+
+/// The campaigns saved in the subdirectories of `saves`, as the game lays them out, most
+/// recently saved first.  Only their descriptors are read, leaving each save to be parsed when
+/// it's wanted.
+pub fn find_campaigns(saves: &Path) -> std::io::Result<Vec<Campaign>> {
+    let mut campaigns = vec![];
+    for entry in std::fs::read_dir(saves)? {
+        let directory = entry?.path();
+        if !directory.is_dir() {
+            continue;
+        }
+        let Some(path) = ["campaign.xml", "campaign.xml.gz"]
+            .iter()
+            .map(|name| directory.join(name))
+            .find(|path| path.is_file())
+        else {
+            continue;
+        };
+        let descriptor = load_descriptor(&path).unwrap_or_default();
+        campaigns.push(Campaign { path, descriptor });
+    }
+    campaigns.sort_by_cached_key(|c| {
+        std::cmp::Reverse(c.descriptor.save_date.as_deref().map(date_order))
+    });
+    Ok(campaigns)
+}
+
+/// The numbers in a save date, to put dates in order by rather than their text: the cycle,
+/// month and day of an in-game date like `c.206.10`, or year down to seconds of a timestamp.
+pub fn date_order(date: &str) -> Vec<u64> {
+    date.split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+/// Extract the major and minor version from a game version like `0.96a-RC10`.
+pub fn parse_game_version(version: &str) -> Option<(u32, u32)> {
+    let numeric = version
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()?;
+    let mut parts = numeric.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Everything read from a campaign save.
+#[derive(Clone, Debug, Default)]
+pub struct Sector {
+    pub systems: Vec<System>,
+    /// The relationship of each faction, by id, with the player.
+    pub relationships: BTreeMap<String, f64>,
+    /// Parts of the save that couldn't be read and were skipped, by where they start.
+    pub problems: Vec<String>,
+    /// Where storms were on the go in hyperspace when the save was made.
+    pub storms: Vec<StormCell>,
+}
+
+/// Which kinds of object to read from a save.  Planets are always read, but skipping the rest
+/// makes enormous saves quicker to load and smaller in memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Stations, relays, salvage and other custom entities.
+    pub entities: bool,
+    /// Fleets other than the player's, which is always kept to know where they are.
+    pub fleets: bool,
+    /// Nebulae, fields and rings, whether terrain or scenery.
+    pub terrain: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            entities: true,
+            fleets: true,
+            terrain: true,
+        }
+    }
+}
+
+/// Load the star systems from the save at `path`.
+pub fn load_save(path: &Path) -> Result<Vec<System>, ParseError> {
+    load_sector(path).map(|sector| sector.systems)
+}
+
+/// Parse the star systems from the contents of a save.
+pub fn parse_save(xml: &str) -> Result<Vec<System>, ParseError> {
+    read_save(xml.as_bytes(), ParseOptions::default()).map(|sector| sector.systems)
+}
+
+/// Load the systems and faction relationships from the save at `path`.
+///
+/// Gzip-compressed saves are detected by their magic bytes and decompressed on the fly.
+pub fn load_sector(path: &Path) -> Result<Sector, ParseError> {
+    load_sector_with(path, ParseOptions::default())
+}
+
+/// Load the save at `path` as [`load_sector`] does, reading only the kinds of object `options`
+/// ask for.
+pub fn load_sector_with(path: &Path, options: ParseOptions) -> Result<Sector, ParseError> {
+    read_sector_with(File::open(path)?, options)
+}
+
+/// Read the systems and faction relationships from a save being streamed in, such as on
+/// standard input, compressed or not.
+pub fn read_sector<R: Read>(save: R) -> Result<Sector, ParseError> {
+    read_sector_with(save, ParseOptions::default())
+}
+
+/// Read a save being streamed in as [`read_sector`] does, reading only the kinds of object
+/// `options` ask for.
+pub fn read_sector_with<R: Read>(save: R, options: ParseOptions) -> Result<Sector, ParseError> {
+    let mut save = BufReader::new(save);
+    if save.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        read_save(
+            BufReader::new(flate2::bufread::GzDecoder::new(save)),
+            options,
+        )
+    } else {
+        read_save(save, options)
+    }
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+fn read_save<R: BufRead>(save: R, options: ParseOptions) -> Result<Sector, ParseError> {
+    let mut sector = Sector::default();
+    let mut first_error = None;
+
+    let classes = [
+        "Sstm",
+        "Fctn",
+        HYPERSPACE_TERRAIN,
+        "HyperspaceTerrainPlugin",
+    ];
+    let result = for_each_element(save, &classes, |class, fragment, line| {
+        // A system the parser chokes on shouldn't cost the user every other one
+        let doc = match roxmltree::Document::parse(fragment) {
+            Ok(doc) => doc,
+            Err(e) => {
+                sector.problems.push(describe_xml_error(class, &e, line));
+                first_error.get_or_insert(e);
+                return Ok(());
+            }
+        };
+        // Whichever XStream met first holds the other, when a faction's first seen in a system
+        // or the other way round
+        sector.systems.extend(
+            doc.descendants()
+                .filter(|n| is_class(n, "Sstm"))
+                .filter_map(|sys| extract_system(&sys, options)),
+        );
+        let factions = doc.descendants().filter(|n| is_class(n, "Fctn"));
+        sector
+            .relationships
+            .extend(factions.filter_map(|faction| extract_relationship(&faction)));
+        // Hyperspace is as likely to turn up in a system, through its jump points
+        let hyperspace = doc.descendants().filter(|n| {
+            n.attribute("cl")
+                .is_some_and(|c| c.rsplit('.').next() == Some("HyperspaceTerrainPlugin"))
+        });
+        sector
+            .storms
+            .extend(hyperspace.flat_map(|plugin| extract_storms(&plugin)));
+        Ok(())
+    });
+
+    // Keep what was read before a truncated or otherwise unreadable stream gave out
+    match result {
+        Err(e) if sector.systems.is_empty() => return Err(e),
+        Err(e) => sector
+            .problems
+            .push(format!("The rest of the save was skipped. {e}")),
+        Ok(()) => {}
+    }
+    // Nothing to salvage means the save as a whole is unreadable
+    if let Some(e) = first_error.filter(|_| sector.systems.is_empty()) {
+        return Err(e.into());
+    }
+
+    sector.systems.sort_unstable_by_key(|s| s.name.clone());
+
+    for system in &sector.systems {
+        match system.objects.iter().filter(|o| o.raw.unreadable).count() {
+            0 => {}
+            1 => sector.problems.push(format!(
+                "Guessed the name of an object in {}, whose details couldn't be read",
+                system.name
+            )),
+            guessed => sector.problems.push(format!(
+                "Guessed the names of {guessed} objects in {}, whose details couldn't be read",
+                system.name
+            )),
+        }
+    }
+
+    for object in sector.systems.iter_mut().flat_map(|s| &mut s.objects) {
+        object.relationship = object
+            .faction
+            .as_ref()
+            .and_then(|f| sector.relationships.get(f))
+            .copied();
+    }
+
+    Ok(sector)
+}
+
+/// Describe a parse error in the element of `class` starting at `line` of the save, with the
+/// position made relative to the whole save rather than the element.
+fn describe_xml_error(class: &str, error: &roxmltree::Error, line: usize) -> String {
+    let pos = error.pos();
+    let message = error.to_string();
+    let message = message
+        .strip_suffix(&format!(" at {pos}"))
+        .unwrap_or(&message);
+    let what = match class {
+        "Sstm" => "a system",
+        "Fctn" => "a faction",
+        _ => "the hyperspace storms",
+    };
+    format!(
+        "Skipped {what}: {message} at line {}, column {}",
+        line + pos.row as usize - 1,
+        pos.col
+    )
+}
+
+/// Stream `reader`, calling `f` with the class, source and starting line of each outermost
+/// element of one of `classes` found.
+///
+/// Only the elements we're interested in are ever held in memory, rather than the entire
+/// save and a document tree on top of it.  This relies on `<` and `>` being escaped outside
+/// of markup, which XStream takes care of, though comments and CDATA sections may hold them.
+fn for_each_element<R: BufRead>(
+    mut reader: R,
+    classes: &[&'static str],
+    mut f: impl FnMut(&str, &str, usize) -> Result<(), ParseError>,
+) -> Result<(), ParseError> {
+    let class_attributes: Vec<_> = classes.iter().map(|c| format!(" cl=\"{c}\"")).collect();
+    let mut class = "";
+    let mut open = String::new();
+    let mut close = String::new();
+    let mut text = vec![];
+    let mut markup = vec![];
+    let mut element = vec![];
+    let mut depth = 0usize;
+    let mut line = 1;
+    let mut start_line = 1;
+
+    loop {
+        text.clear();
+        markup.clear();
+        reader.read_until(b'<', &mut text)?;
+        line += text.iter().filter(|&&b| b == b'\n').count();
+        if text.pop() == Some(b'<') {
+            markup.push(b'<');
+            reader.read_until(b'>', &mut markup)?;
+            // Read on past any `>` within a comment or CDATA section to where it really ends
+            for (opener, closer) in [(&b"<!--"[..], &b"-->"[..]), (b"<![CDATA[", b"]]>")] {
+                while markup.starts_with(opener)
+                    && (markup.len() < opener.len() + closer.len() || !markup.ends_with(closer))
+                {
+                    if reader.read_until(b'>', &mut markup)? == 0 {
+                        break;
+                    }
+                }
+            }
+        }
+        if depth > 0 {
+            element.extend_from_slice(&text);
+        }
+        if !markup.ends_with(b">") {
+            if depth > 0 {
+                let message = format!("the save ends inside the element at line {start_line}");
+                return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, message).into());
+            }
+            return Ok(());
+        }
+        let markup_line = line;
+        line += markup.iter().filter(|&&b| b == b'\n').count();
+
+        if depth == 0 {
+            let Some(name) = element_name(&markup) else { continue };
+            let Some(found) = classes
+                .iter()
+                .zip(&class_attributes)
+                .find(|(c, attribute)| name == **c || contains(&markup, attribute.as_bytes()))
+            else {
+                continue;
+            };
+            class = found.0;
+            start_line = markup_line;
+            open = format!("<{name}");
+            close = format!("</{name}");
+        }
+        element.extend_from_slice(&markup);
+
+        if is_tag(&markup, &open) && !markup.ends_with(b"/>") {
+            depth += 1;
+        } else if is_tag(&markup, &close) {
+            depth -= 1;
+        }
+
+        if depth == 0 {
+            // Stray bytes that aren't UTF-8 are replaced rather than failing the whole element
+            let source = String::from_utf8_lossy(&element);
+            f(class, &source, start_line)?;
+            element.clear();
+        }
+    }
+}
+
+/// The name of the element opened by `markup`, if it's an opening tag.
+fn element_name(markup: &[u8]) -> Option<&str> {
+    let name = markup.strip_prefix(b"<")?;
+    let end = name
+        .iter()
+        .position(|b| matches!(b, b' ' | b'\t' | b'\r' | b'\n' | b'/' | b'>'))?;
+    match &name[..end] {
+        [] | [b'?' | b'!', ..] => None,
+        name => std::str::from_utf8(name).ok(),
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+fn is_tag(markup: &[u8], prefix: &str) -> bool {
+    markup.starts_with(prefix.as_bytes())
+        && matches!(
+            markup.get(prefix.len()),
+            Some(b' ' | b'\t' | b'\r' | b'\n' | b'/' | b'>')
+        )
+}
+
+/// Whether `node` is an XStream-serialized object of `class`.
+///
+/// Objects are usually written as elements named after their class alias, but when one is
+/// first encountered in a field of a more general type the element is named after the field
+/// and the alias moves into a `cl` attribute, as with `<cL cl="Sstm">`.
+fn is_class(node: &Node, class: &str) -> bool {
+    node.is_element() && node.attribute("cl").unwrap_or(node.tag_name().name()) == class
+}
+
+/// Descendants of `sys` that are objects of `class` belonging to it, rather than to any
+/// other system serialized within it.
+fn members<'a, 'input>(
+    sys: &Node<'a, 'input>,
+    class: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    let sys = *sys;
+    sys.descendants().filter(move |n| {
+        is_class(n, class) && n.ancestors().skip(1).find(|a| is_class(a, "Sstm")) == Some(sys)
+    })
+}
+
+/// Descendants of `node` describing the object itself, rather than another object serialized
+/// within it, such as a fully-serialized orbit focus.
+fn own_descendants<'a, 'input>(
+    node: &Node<'a, 'input>,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    let node = *node;
+    node.descendants().skip(1).filter(move |n| {
+        n.ancestors().take_while(|a| *a != node).all(|a| {
+            !is_class(&a, "Plnt") && !is_class(&a, "CCEnt") && a.tag_name().name() != "orbit"
+        })
+    })
+}
+
+fn extract_system(sys: &Node, options: ParseOptions) -> Option<System> {
+    let name = sys.attribute("bN")?;
+    let mut system = System {
+        name: name.to_string(),
+        lc_name: name.to_lowercase(),
+        internal_id: child_text(sys, "id").map(str::to_string),
+        location: child_text(sys, "loc").and_then(parse_vector),
+        objects: vec![],
+        mission: false,
+        terrain: members(sys, "CTEnt")
+            .filter(|_| options.terrain)
+            .filter_map(|t| extract_terrain(&t))
+            .collect(),
+        fleets: members(sys, "CFleet")
+            .filter(|fleet| options.fleets || fleet.tag_name().name() == "playerFleet")
+            .map(|fleet| Fleet {
+                faction: faction(&fleet),
+                // Written under the campaign's field for it, rather than as a plain fleet
+                player: fleet.tag_name().name() == "playerFleet",
+                station: child_text(&fleet, "stationMode") == Some("true"),
+            })
+            .collect(),
+        entered: child_text(sys, "lastPlayerVisitTimestamp")
+            .and_then(|t| t.parse::<i64>().ok())
+            .is_some_and(|t| t != 0),
+        procgen: child_text(sys, "procgen") == Some("true"),
+        constellation: extract_constellation(sys),
+    };
+
+    let mut orbit_foci = vec![];
+
+    for planet in members(sys, "Plnt") {
+        let Some(mut object) = extract_object(&planet) else { continue };
+        object.kind = Kind::Planet;
+        system.objects.push(object);
+        orbit_foci.push(orbit_focus(&planet));
+    }
+
+    for ent in members(sys, "CCEnt").filter(|_| options.entities) {
+        let Some(mut object) = extract_object(&ent) else { continue };
+        // Entities share the `type` field, but use it for custom entity ids
+        object.entity_type = object.planet_type.take();
+        system.objects.push(object);
+        orbit_foci.push(orbit_focus(&ent));
+    }
+    system.mission = system.objects.iter().any(|ent| ent.mission);
+
+    for (index, focus) in orbit_foci.into_iter().enumerate() {
+        system.objects[index].parent = focus.and_then(|focus| {
+            system
+                .objects
+                .iter()
+                .position(|o| o.id.as_deref() == Some(focus.as_str()))
+        });
+        // Without anything to face, not spinning isn't tidal locking
+        let object = &mut system.objects[index];
+        object.tidally_locked &= object.parent.is_some();
+    }
+
+    // Unlike ring terrain, ring bands are only scenery, drawn around whatever they orbit
+    for band in members(sys, "RingBand").filter(|_| options.terrain) {
+        let number = |tag| child_text(&band, tag).and_then(parse_number);
+        let (Some(focus), Some(middle)) = (orbit_focus(&band), number("middleRadius")) else {
+            continue;
+        };
+        let half_width = number("bandWidthInEngine").unwrap_or(0.0) / 2.0;
+        let Some(object) = system
+            .objects
+            .iter_mut()
+            .find(|o| o.id.as_deref() == Some(focus.as_str()))
+        else {
+            continue;
+        };
+        object.rings.push(Terrain {
+            kind: TerrainKind::Ring,
+            center: object.pos,
+            inner_radius: (middle - half_width).max(0.0),
+            outer_radius: middle + half_width,
+        });
+    }
+
+    Some(system)
+}
+
+/// The name of a system's constellation, made up as the game does from the name picked for
+/// it and whether it's a nebula.  Systems after the first in a constellation refer back to
+/// where it was written, which can only be followed within the same system element.
+fn extract_constellation(sys: &Node) -> Option<String> {
+    let mut constellation = sys
+        .children()
+        .find(|n| n.tag_name().name() == "constellation")?;
+    if let Some(id) = constellation.attribute("ref") {
+        constellation = sys
+            .document()
+            .descendants()
+            .find(|n| n.attribute("z") == Some(id))?;
+    }
+    // Its systems are often written out within it, so look for the name where it's kept
+    let pick = constellation
+        .children()
+        .find(|n| n.tag_name().name() == "namePick")?;
+    let name = own_text(&pick, "name")?;
+    Some(match child_text(&constellation, "type") {
+        Some("NEBULA") => format!("{name} Nebula"),
+        _ => format!("{name} Constellation"),
+    })
+}
+
+/// Parse an `x|y` vector, ignoring whitespace around components and trailing delimiters.  A
+/// missing or extra component anywhere else makes it unreadable.
+pub fn parse_vector(v: &str) -> Option<Position> {
+    let v = v.trim_end_matches(|c: char| c == '|' || c.is_whitespace());
+    let (x, y) = v.split_once('|')?;
+    if y.contains('|') {
+        return None;
+    }
+    Some(Position {
+        x: parse_number(x)?,
+        y: parse_number(y)?,
+    })
+}
+
+/// Parse a finite number, accepting scientific notation and the separators written by some
+/// locales.  Commas splitting the whole part into thousands, as in `1,000,000`, are dropped;
+/// otherwise a lone comma without a point is taken to be the decimal separator, as in `1,5`.
+pub fn parse_number(n: &str) -> Option<f64> {
+    let n = n.trim();
+    let whole = n.split(['.', 'e', 'E']).next().unwrap_or(n);
+    let mut groups = whole.split(',');
+    let leading = groups
+        .next()
+        .unwrap_or(whole)
+        .trim_start_matches(['-', '+']);
+    let thousands = whole.contains(',')
+        && (1..=3).contains(&leading.len())
+        && groups.all(|g| g.len() == 3 && g.bytes().all(|b| b.is_ascii_digit()));
+
+    let number = if thousands {
+        n.replace(',', "")
+    } else if n.matches(',').count() == 1 && !n.contains('.') {
+        n.replace(',', ".")
+    } else {
+        n.to_string()
+    };
+    number.parse().ok().filter(|n: &f64| n.is_finite())
+}
+
+/// The angle, radius and period of an object's `orbit` element, XStream's `a`, `r` and `p`.
+/// Only the orbit's own fields are read, not those of a focus serialized within it.
+fn extract_orbit(node: &Node) -> Option<Orbit> {
+    let orbit = node.children().find(|n| n.tag_name().name() == "orbit")?;
+    let field = |tag| {
+        orbit
+            .children()
+            .find(|n| n.tag_name().name() == tag)
+            .and_then(|n| n.text())
+            .and_then(parse_number)
+    };
+    Some(Orbit {
+        angle: field("a"),
+        radius: field("r"),
+        period: field("p"),
+    })
+}
+
+/// The id of the entity an object orbits, from the focus (`f`) of its `orbit` element.  The
+/// focus is either a reference to an entity serialized elsewhere, or the entity itself.
+fn orbit_focus(node: &Node) -> Option<String> {
+    let orbit = node.children().find(|n| n.tag_name().name() == "orbit")?;
+    let focus = orbit.children().find(|n| n.tag_name().name() == "f")?;
+    focus
+        .attribute("ref")
+        .or_else(|| focus.attribute("z"))
+        .map(str::to_string)
+}
+
+fn extract_object(node: &Node) -> Option<Object> {
+    let loc = parse_vector(own_text(node, "loc")?)?;
+
+    let missions: Vec<Mission> = own_descendants(node)
+        .filter(|n| n.tag_name().name() == "MReq")
+        .map(|req| Mission {
+            kind: child_text(&req, "type")
+                .or_else(|| child_text(&req, "reason"))
+                .map(str::to_string),
+            faction: child_text(&req, "faction").map(str::to_string),
+            reward: child_text(&req, "reward").and_then(parse_number),
+        })
+        .collect();
+
+    let market = own_descendants(node)
+        .find(|n| n.tag_name().name() == "Mrkt")
+        .map(|market| extract_market(&market));
+
+    let mut salvage: Vec<String> = own_descendants(node)
+        .filter(|n| n.tag_name().name() == "DropData")
+        .filter_map(|drop| Some(child_text(&drop, "group")?.to_string()))
+        .collect();
+    salvage.dedup();
+
+    let discovered = !node
+        .children()
+        .any(|n| n.tag_name().name() == "discoverable" && n.text() == Some("true"));
+
+    let rotation = node
+        .children()
+        .find(|n| n.tag_name().name() == "spec")
+        .and_then(|spec| child_text(&spec, "rotation"))
+        .and_then(parse_number);
+
+    let j0 = own_text(node, "j0");
+    let name = j0.and_then(|j0| json::parse(j0).ok()).and_then(|what| {
+        let name = what.entries().find(|e| e.0 == "f0")?.1.as_str()?;
+        Some(name.to_string())
+    });
+    let unreadable = name.is_none();
+
+    Some(Object {
+        name: name.or_else(|| fallback_name(node, j0))?,
+        kind: Kind::Entity,
+        pos: loc,
+        mission: !missions.is_empty(),
+        missions,
+        id: node.attribute("z").map(str::to_string),
+        internal_id: child_text(node, "id").map(str::to_string),
+        parent: None,
+        orbit: extract_orbit(node),
+        discovered,
+        radius: child_text(node, "radius").and_then(parse_number),
+        planet_type: child_text(node, "type").map(str::to_string),
+        entity_type: None,
+        atmosphere: market
+            .as_ref()
+            .and_then(|m| Atmosphere::from_conditions(m.conditions.iter().map(String::as_str))),
+        temperature: market
+            .as_ref()
+            .map(|m| Temperature::from_conditions(m.conditions.iter().map(String::as_str))),
+        market,
+        salvage,
+        tidally_locked: rotation == Some(0.0),
+        rings: vec![],
+        faction: faction(node),
+        relationship: None,
+        tags: node
+            .children()
+            .filter(|n| n.tag_name().name() == "tags")
+            .flat_map(|tags| tags.children())
+            .filter_map(|tag| Some(tag.text()?.trim().to_string()))
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        scanned: memory_flag(node, GATE_SCANNED),
+        raw: RawData {
+            j0: j0.map(str::to_string),
+            attributes: node
+                .attributes()
+                .map(|a| (a.name().to_string(), a.value().to_string()))
+                .collect(),
+            unreadable,
+        },
+    })
+}
+
+/// A name for an object whose `j0` is missing or can't be read, as after the game changes how
+/// it's written: from a `name` element, picked out of the `j0` text by hand, or failing those
+/// its type.
+fn fallback_name(node: &Node, j0: Option<&str>) -> Option<String> {
+    let picked = || {
+        let j0 = j0?;
+        let rest = j0[j0.find("\"f0\"")? + 4..].trim_start();
+        let rest = rest.strip_prefix(':')?.trim_start().strip_prefix('"')?;
+        Some(rest[..rest.find('"')?].to_string())
+    };
+    own_text(node, "name")
+        .map(str::to_string)
+        .or_else(picked)
+        .or_else(|| child_text(node, "type").map(str::to_string))
+}
+
+/// The id of the faction owning `node`, given as either the text of its `faction` element or
+/// the id of a faction serialized within it.
+fn faction(node: &Node) -> Option<String> {
+    node.children()
+        .find(|n| n.tag_name().name() == "faction")
+        .and_then(|f| child_text(&f, "id").or_else(|| f.text().map(str::trim)))
+        .filter(|f| !f.is_empty())
+        .map(str::to_string)
+}
+
+/// A terrain entity, whose `plugin` describes its shape.  Belts and rings are bands either side
+/// of a middle radius; nebulae are approximated by their overall radius.
+/// Read the storm cells from `plugin`, the hyperspace terrain's.
+///
+/// Only the cells with something going on are kept, under `savedActiveCells`, each by its
+/// column `i` and row `j` in a grid of `params`' `w` by `h` tiles centred on the terrain entity
+/// holding the plugin.  Cells that are quiet or only waiting their turn are left out.
+fn extract_storms(plugin: &Node) -> Vec<StormCell> {
+    let origin = plugin
+        .parent()
+        .and_then(|entity| child_text(&entity, "loc"))
+        .and_then(parse_vector)
+        .unwrap_or(Position { x: 0.0, y: 0.0 });
+    let child = |tag| plugin.children().find(|n| n.tag_name().name() == tag);
+    let (Some(params), Some(cells)) = (child("params"), child("savedActiveCells")) else {
+        return vec![];
+    };
+    let dimension = |tag| child_text(&params, tag).and_then(parse_number);
+    let (Some(columns), Some(rows)) = (dimension("w"), dimension("h")) else { return vec![] };
+
+    let size = HYPERSPACE_TILE_SIZE;
+    let left = origin.x - columns * size / 2.0;
+    let bottom = origin.y - rows * size / 2.0;
+    cells
+        .children()
+        .filter(|cell| cell.is_element())
+        .filter_map(|cell| {
+            let number = |tag| child_text(&cell, tag).and_then(parse_number);
+            let raging = match child_text(&cell, "state")? {
+                "STORM" => true,
+                "SIGNAL" | "STORM_WANE" => false,
+                _ => return None,
+            };
+            let (i, j) = (number("i")?, number("j")?);
+            Some(StormCell {
+                center: Position {
+                    x: left + (i + 0.5) * size,
+                    y: bottom + (j + 0.5) * size,
+                },
+                size,
+                raging,
+            })
+        })
+        .collect()
+}
+
+fn extract_terrain(node: &Node) -> Option<Terrain> {
+    let center = parse_vector(own_text(node, "loc")?)?;
+    let plugin = node.children().find(|n| n.tag_name().name() == "plugin")?;
+    let kind = TerrainKind::from_plugin(plugin.attribute("cl")?)?;
+    let number = |tag| child_text(&plugin, tag).and_then(parse_number);
+
+    let (inner_radius, outer_radius) = match kind {
+        TerrainKind::Nebula => (0.0, number("radius")?),
+        TerrainKind::AsteroidBelt | TerrainKind::Ring => {
+            let middle = number("middleRadius")?;
+            let half_width = number("bandWidthInEngine")? / 2.0;
+            ((middle - half_width).max(0.0), middle + half_width)
+        }
+    };
+
+    Some(Terrain {
+        kind,
+        center,
+        inner_radius,
+        outer_radius,
+    })
+}
+
+/// The player relationship of a faction, from its `relationships` map of faction ids to
+/// reputation, which XStream writes as `<entry>` pairs of key and value.
+fn extract_relationship(faction: &Node) -> Option<(String, f64)> {
+    let id = child_text(faction, "id")?;
+    let relationships = faction
+        .children()
+        .find(|n| n.tag_name().name() == "relationships")?;
+
+    relationships
+        .children()
+        .filter(|n| n.tag_name().name() == "entry")
+        .find_map(|entry| {
+            let mut values = entry.children().filter(|n| n.is_element());
+            let key = values.next()?.text()?.trim();
+            let value = parse_number(values.next()?.text()?)?;
+            (key == PLAYER_FACTION).then(|| (id.to_string(), value))
+        })
+}
+
+fn extract_market(node: &Node) -> Market {
+    let commodities = node
+        .descendants()
+        .filter(|n| n.tag_name().name() == "CoM")
+        .filter_map(|com| {
+            Some(Commodity {
+                id: child_text(&com, "id")?.to_string(),
+                available: parse_number(child_text(&com, "available")?)?,
+                demand: parse_number(child_text(&com, "demand")?)?,
+            })
+        })
+        .collect();
+
+    let conditions: Vec<String> = node
+        .descendants()
+        .filter(|n| n.tag_name().name() == "MCon")
+        .filter_map(|cond| Some(child_text(&cond, "id")?.to_string()))
+        .collect();
+
+    let industries = node
+        .descendants()
+        .filter(|n| n.tag_name().name() == "Ind")
+        .filter_map(|ind| Some(child_text(&ind, "id")?.to_string()))
+        .collect();
+
+    // Setting a colony free adds the free_market condition as well as the flag, and saves
+    // from some versions only have the one.
+    let free_port = child_text(node, "freePort") == Some("true")
+        || conditions.iter().any(|c| c == "free_market");
+
+    Market {
+        free_port,
+        conditions,
+        commodities,
+        industries,
+        size: child_text(node, "size").and_then(|s| s.parse().ok()),
+        survey: child_text(node, "surveyLevel").and_then(SurveyLevel::from_id),
+    }
+}
+
+/// The text of the `tag` element describing `node` itself, preferring a direct child over the
+/// first descendant so that fields of nested objects, such as a fully-serialized orbit focus,
+/// aren't mistaken for its own.
+fn own_text<'a>(node: &Node<'a, '_>, tag: &str) -> Option<&'a str> {
+    child_text(node, tag).or_else(|| {
+        node.descendants()
+            .find(|n| n.tag_name().name() == tag)?
+            .text()
+            .map(str::trim)
+    })
+}
+
+/// Whether the memory saved under `node` sets the flag `key`, written as an element holding the
+/// key followed by one holding its value.
+fn memory_flag(node: &Node, key: &str) -> bool {
+    own_descendants(node)
+        .filter(|n| n.text() == Some(key))
+        .any(|n| n.next_sibling_element().and_then(|v| v.text()) == Some("true"))
+}
+
+fn child_text<'a>(node: &Node<'a, '_>, tag: &str) -> Option<&'a str> {
+    node.children()
+        .find(|n| n.tag_name().name() == tag)?
+        .text()
+        .map(str::trim)
+}
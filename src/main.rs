@@ -1,277 +1,5270 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
 
 use eframe::egui;
-use egui::plot::{PlotPoint, Text};
-use egui::{ComboBox, Ui};
-use roxmltree::Node;
+use egui::plot::{MarkerShape, PlotPoint, PlotUi, Text};
+use egui::{Color32, ComboBox, RichText, Ui};
+use scansector::{
+    all_objects, anomalies, changes, core_distance, date_order, estimated_accessibility_penalty,
+    export, faction_name, find_campaigns, home_world_candidates, industry_name, load_descriptor,
+    load_sector, load_sector_with, marks_procgen, read_sector, report, sheet, survey_progress,
+    territory, trade_links, trails, unique_structures, unvisited_systems, Anomaly, Atmosphere,
+    Campaign, Category, Change, Danger, Defense, Descriptor, GateState, JumpPointKind, Kind,
+    Market, MissionType, Multiplicity, Object, ObjectChange, ParseOptions, Position, Progress,
+    Resource, Sector, Size, Standing, StarClass, StellarHazard, StormCell, Supply, SurveyLevel,
+    System, Temperature, Terrain, TerrainKind, Unique, PLAYER_FACTION, UNITS_PER_LIGHT_YEAR,
+};
 
+/// Marker colours for each category of object.
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct Palette {
+    planet: Color32,
+    entity: Color32,
+    mission: Color32,
+    unique: Color32,
+    hostile: Color32,
+    friendly: Color32,
+    neutral: Color32,
+}
+
+/// The defaults are drawn from the Okabe-Ito palette, which stays distinguishable with the
+/// common colour-vision deficiencies.  Its yellow and black are left out as they vanish
+/// against the light and dark themes respectively.
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            planet: Color32::from_rgb(0x56, 0xb4, 0xe9),
+            entity: Color32::from_rgb(0xe6, 0x9f, 0x00),
+            mission: Color32::from_rgb(0xcc, 0x79, 0xa7),
+            unique: Color32::from_rgb(0x00, 0x9e, 0x73),
+            hostile: Color32::from_rgb(0xd5, 0x5e, 0x00),
+            friendly: Color32::from_rgb(0x00, 0x72, 0xb2),
+            neutral: Color32::from_rgb(0x99, 0x99, 0x99),
+        }
+    }
+}
+
+impl Palette {
+    fn color(&self, object: &Object) -> Color32 {
+        if object.mission {
+            self.mission
+        } else if object.unique().is_some() {
+            self.unique
+        } else {
+            match object.kind {
+                Kind::Planet => self.planet,
+                Kind::Entity => self.entity,
+            }
+        }
+    }
+
+    fn edit(&mut self, ui: &mut Ui) {
+        egui::Grid::new("palette").show(ui, |ui| {
+            for (label, color) in [
+                ("Planets", &mut self.planet),
+                ("Entities", &mut self.entity),
+                ("Missions", &mut self.mission),
+                ("Unique Structures", &mut self.unique),
+                ("Hostile", &mut self.hostile),
+                ("Friendly", &mut self.friendly),
+                ("Neutral", &mut self.neutral),
+            ] {
+                ui.label(label);
+                ui.color_edit_button_srgba(color);
+                ui.end_row();
+            }
+        });
+
+        if ui.button("Reset Colours").clicked() {
+            *self = Self::default();
+        }
+    }
+}
+
+/// Which objects are shown on the plot and in the object list.
+#[derive(Debug, Clone, Copy)]
+struct Visibility {
+    planets: bool,
+    stations: bool,
+    jump_points: bool,
+    entities: bool,
+    undiscovered: bool,
+    missions_only: bool,
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Self {
+            planets: true,
+            stations: true,
+            jump_points: true,
+            entities: true,
+            undiscovered: false,
+            missions_only: false,
+        }
+    }
+}
+
+impl Visibility {
+    fn shows(&self, object: &Object) -> bool {
+        self.shows_category(object.category())
+            && (self.undiscovered || object.discovered)
+            && (!self.missions_only || object.mission)
+    }
+
+    fn shows_category(&self, category: Category) -> bool {
+        match category {
+            Category::Planet => self.planets,
+            Category::Station => self.stations,
+            Category::JumpPoint => self.jump_points,
+            Category::Entity => self.entities,
+        }
+    }
+
+    fn toggle_category(&mut self, category: Category) {
+        let shown = match category {
+            Category::Planet => &mut self.planets,
+            Category::Station => &mut self.stations,
+            Category::JumpPoint => &mut self.jump_points,
+            Category::Entity => &mut self.entities,
+        };
+        *shown = !*shown;
+    }
+}
+
+/// How systems are drawn on the plot.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct PlotOptions {
+    palette: Palette,
+    show_orbits: bool,
+    show_labels: bool,
+    label_size: f32,
+    /// Space to leave around the system's objects, as a fraction of its span.
+    padding: f64,
+    /// Colour objects by how their owner regards the player, rather than by kind.
+    color_by_standing: bool,
+    show_terrain: bool,
+    /// Frame each system afresh on switching to it, rather than keeping the current view.
+    auto_fit: bool,
+    /// Draw lines on the sector map from the player's colonies to the nearest markets.
+    trade_links: bool,
+    /// Shade where storms are on the go in hyperspace on the sector map.
+    show_storms: bool,
+    /// Mark systems that stand out from the procedural norm, in the list and on the sector map.
+    highlight_anomalies: bool,
+    /// Put the primary star at the origin, so positions read as distances from it.
+    star_relative: bool,
+    /// Mark mission targets by the type of mission, rather than all alike.
+    mission_types: bool,
+    /// Draw where objects were in the timeline's saves, joined up in save date order.
+    show_trails: bool,
+    /// List the current system's objects in the table in place of the plot, for where the plot
+    /// doesn't draw properly.
+    table_only: bool,
+    /// Pick out what's appeared, disappeared or changed when the save is reloaded.
+    highlight_changes: bool,
+}
+
+impl PlotOptions {
+    fn color(&self, object: &Object) -> Color32 {
+        match object.standing().filter(|_| self.color_by_standing) {
+            Some(Standing::Hostile) => self.palette.hostile,
+            Some(Standing::Friendly) => self.palette.friendly,
+            Some(Standing::Neutral) => self.palette.neutral,
+            None => self.palette.color(object),
+        }
+    }
+}
+
+impl Default for PlotOptions {
+    fn default() -> Self {
+        Self {
+            palette: Palette::default(),
+            show_orbits: false,
+            show_labels: true,
+            label_size: 14.0,
+            padding: 0.05,
+            color_by_standing: false,
+            show_terrain: true,
+            auto_fit: true,
+            trade_links: false,
+            show_storms: true,
+            highlight_anomalies: false,
+            star_relative: false,
+            mission_types: false,
+            show_trails: false,
+            table_only: false,
+            highlight_changes: true,
+        }
+    }
+}
+
+/// The text in the filter box, matched as a case-insensitive substring or optionally as a
+/// regular expression.
+#[derive(Debug, Default)]
+struct NameFilter {
+    text: String,
+    lc_text: String,
+    use_regex: bool,
+    regex: Option<regex::Regex>,
+    /// Why the text isn't a valid pattern, in regex mode.
+    error: Option<String>,
+}
+
+impl NameFilter {
+    /// Bring the lowercased text and compiled pattern up to date after editing.
+    fn update(&mut self) {
+        self.lc_text = self.text.to_lowercase();
+        self.regex = None;
+        self.error = None;
+        if self.use_regex && !self.text.is_empty() {
+            match regex::RegexBuilder::new(&self.text)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(regex) => self.regex = Some(regex),
+                Err(e) => self.error = Some(e.to_string()),
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.text.clear();
+        self.update();
+    }
+
+    fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Whether a lowercased name matches.  An invalid pattern matches everything, leaving the
+    /// list as it was until it's fixed.
+    fn matches(&self, lc_name: &str) -> bool {
+        match &self.regex {
+            Some(regex) => regex.is_match(lc_name),
+            None if self.use_regex => true,
+            None => lc_name.contains(&self.lc_text),
+        }
+    }
+}
+
+/// What the filter box applies to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum SearchScope {
+    /// Narrow the system list to names containing the filter.
+    #[default]
+    Systems,
+    /// List objects across the whole sector with names containing the filter.
+    Objects,
+}
+
+impl SearchScope {
+    const ALL: [SearchScope; 2] = [SearchScope::Systems, SearchScope::Objects];
+
+    fn name(self) -> &'static str {
+        match self {
+            SearchScope::Systems => "Filter Systems",
+            SearchScope::Objects => "Search All Objects",
+        }
+    }
+}
+
+/// Common queries that can be switched on with a click, rather than assembled from filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+enum QuickFilter {
+    Habitable,
+    OreRich,
+    Volatiles,
+    HasMission,
+    Unsurveyed,
+    MyColonies,
+}
+
+impl QuickFilter {
+    const ALL: [QuickFilter; 6] = [
+        QuickFilter::Habitable,
+        QuickFilter::OreRich,
+        QuickFilter::Volatiles,
+        QuickFilter::HasMission,
+        QuickFilter::Unsurveyed,
+        QuickFilter::MyColonies,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            QuickFilter::Habitable => "Habitable",
+            QuickFilter::OreRich => "Ore Rich",
+            QuickFilter::Volatiles => "Volatiles",
+            QuickFilter::HasMission => "Has Mission",
+            QuickFilter::Unsurveyed => "Unsurveyed",
+            QuickFilter::MyColonies => "My Colonies",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            QuickFilter::Habitable => "Systems with a planet of breathable atmosphere",
+            QuickFilter::OreRich => "Systems with rich or ultrarich ore",
+            QuickFilter::Volatiles => "Systems with any volatiles",
+            QuickFilter::HasMission => "Systems with a mission target",
+            QuickFilter::Unsurveyed => {
+                "Systems with planets not fully surveyed, or objects yet to be discovered"
+            }
+            QuickFilter::MyColonies => "Systems with one of the player's colonies",
+        }
+    }
+
+    fn matches(self, system: &System) -> bool {
+        match self {
+            QuickFilter::Habitable => system
+                .objects
+                .iter()
+                .any(|o| o.atmosphere == Some(Atmosphere::Breathable)),
+            QuickFilter::OreRich => system.resource(Resource::Ore) >= Some(ORE_RICH),
+            QuickFilter::Volatiles => system.resource(Resource::Volatiles).is_some(),
+            QuickFilter::HasMission => system.mission,
+            QuickFilter::Unsurveyed => system
+                .objects
+                .iter()
+                .any(|o| !o.discovered || o.survey().is_some_and(|s| s != SurveyLevel::Full)),
+            QuickFilter::MyColonies => system
+                .objects
+                .iter()
+                .any(|o| o.market.is_some() && o.faction.as_deref() == Some(PLAYER_FACTION)),
+        }
+    }
+}
+
+/// The grade of `ore_rich`, the least the Ore Rich quick filter accepts.
+const ORE_RICH: u8 = 4;
+
+/// Filters of the system list with a checkbox each, as [`ScanSectorUi::toggle_filter`] has
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToggleFilter {
+    Mission,
+    RemnantFleets,
+    Port,
+    FreePort,
+    Blueprints,
+    Uncolonized,
+    StableLocation,
+    AsteroidBelts,
+    HandAuthored,
+    MissingInfrastructure,
+    DomainArray,
+    SoftTargets,
+    Solar,
+}
+
+impl ToggleFilter {
+    const ALL: [ToggleFilter; 13] = [
+        ToggleFilter::Mission,
+        ToggleFilter::RemnantFleets,
+        ToggleFilter::Port,
+        ToggleFilter::FreePort,
+        ToggleFilter::Blueprints,
+        ToggleFilter::Uncolonized,
+        ToggleFilter::StableLocation,
+        ToggleFilter::AsteroidBelts,
+        ToggleFilter::HandAuthored,
+        ToggleFilter::MissingInfrastructure,
+        ToggleFilter::DomainArray,
+        ToggleFilter::SoftTargets,
+        ToggleFilter::Solar,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ToggleFilter::Mission => "Mission Only",
+            ToggleFilter::RemnantFleets => "Remnant Fleets",
+            ToggleFilter::Port => "Has Port",
+            ToggleFilter::FreePort => "Free Port",
+            ToggleFilter::Blueprints => "Blueprint Caches",
+            ToggleFilter::Uncolonized => "Uncolonized",
+            ToggleFilter::StableLocation => "Free Stable Location",
+            ToggleFilter::AsteroidBelts => "Asteroid Belts",
+            ToggleFilter::HandAuthored => "Hand-authored",
+            ToggleFilter::MissingInfrastructure => "Missing Infrastructure",
+            ToggleFilter::DomainArray => "Domain Sensor Array",
+            ToggleFilter::SoftTargets => "Soft Targets",
+            ToggleFilter::Solar => "Good for Solar",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ToggleFilter::Mission => "Systems with a mission target",
+            ToggleFilter::RemnantFleets => "Systems with Remnant fleets about",
+            ToggleFilter::Port => "Systems with a colony that has at least a waystation",
+            ToggleFilter::FreePort => "Systems with a colony open to the black market",
+            ToggleFilter::Blueprints => "Systems with salvage that can yield blueprints",
+            ToggleFilter::Uncolonized => "Systems with planets but no colonies or stations",
+            ToggleFilter::StableLocation => {
+                "Systems with a stable location nothing's been built at"
+            }
+            ToggleFilter::AsteroidBelts => "Systems with a belt that can be mined",
+            ToggleFilter::HandAuthored => {
+                "Core worlds and story systems, rather than generated ones"
+            }
+            ToggleFilter::MissingInfrastructure => {
+                "Systems with one of your colonies but not your own comm relay, nav buoy or \
+                 sensor array"
+            }
+            ToggleFilter::DomainArray => {
+                "Systems with a Domain-era sensor array that's been claimed and is working"
+            }
+            ToggleFilter::SoftTargets => {
+                "Systems with another faction's colony that has little or no defence"
+            }
+            ToggleFilter::Solar => "Systems with a star bright enough for solar arrays",
+        }
+    }
+}
+
+/// Preferences kept between runs.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct Settings {
+    plot: PlotOptions,
+    units: Units,
+    names: NameStyle,
+    /// Reopen the most recent save on startup when none is given on the command line.
+    open_last: bool,
+    /// Select the system the player is in whenever a save is loaded.
+    follow_player: bool,
+    /// How often to check whether the open save has changed, reloading it if so, in seconds.
+    reload_interval: Option<u64>,
+    auto_export: AutoExport,
+    parsing: Parsing,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            plot: PlotOptions::default(),
+            units: Units::default(),
+            names: NameStyle::default(),
+            open_last: false,
+            follow_player: false,
+            reload_interval: Some(5),
+            auto_export: AutoExport::default(),
+            parsing: Parsing::default(),
+        }
+    }
+}
+
+/// What an export of every system is written as.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum ExportFormat {
+    #[default]
+    Json,
+    /// One row per object.
+    Csv,
+}
+
+impl ExportFormat {
+    const ALL: [ExportFormat; 2] = [ExportFormat::Json, ExportFormat::Csv];
+
+    fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+        }
+    }
+
+    fn scope(self) -> ExportScope {
+        match self {
+            ExportFormat::Json => ExportScope::All,
+            ExportFormat::Csv => ExportScope::AllCsv,
+        }
+    }
+}
+
+/// A file written over with an export of the open save each time it's reloaded, so dashboards
+/// and other tools can read the sector as the game goes on.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct AutoExport {
+    enabled: bool,
+    path: String,
+    format: ExportFormat,
+}
+
+/// The kinds of object read from saves, as [`ParseOptions`] has them.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct Parsing {
+    entities: bool,
+    fleets: bool,
+    terrain: bool,
+}
+
+impl Default for Parsing {
+    fn default() -> Self {
+        let options = ParseOptions::default();
+        Self {
+            entities: options.entities,
+            fleets: options.fleets,
+            terrain: options.terrain,
+        }
+    }
+}
+
+impl Parsing {
+    fn options(&self) -> ParseOptions {
+        ParseOptions {
+            entities: self.entities,
+            fleets: self.fleets,
+            terrain: self.terrain,
+        }
+    }
+}
+
+/// The intervals offered for checking the open save for changes, in seconds.
+const RELOAD_INTERVALS: [u64; 5] = [2, 5, 10, 30, 60];
+
+fn interval_text(interval: Option<u64>) -> String {
+    interval.map_or("Off".to_string(), |secs| format!("Every {secs} s"))
+}
+
+impl Settings {
+    fn load(storage: &dyn eframe::Storage) -> Self {
+        eframe::get_value(storage, "settings").unwrap_or_default()
+    }
+
+    fn edit(&mut self, ui: &mut Ui) {
+        ui.strong("System View");
+        ui.checkbox(&mut self.plot.show_orbits, "Show Orbits");
+        ui.checkbox(&mut self.plot.show_terrain, "Show Terrain");
+        ui.checkbox(&mut self.plot.show_labels, "Show Labels");
+        ui.add_enabled(
+            self.plot.show_labels,
+            egui::Slider::new(&mut self.plot.label_size, 6.0..=32.0).text("Label Size"),
+        );
+        ui.add(
+            egui::Slider::new(&mut self.plot.padding, 0.0..=0.5)
+                .custom_formatter(|p, _| format!("{:.0}%", p * 100.0))
+                .text("Padding"),
+        );
+        ui.checkbox(&mut self.plot.color_by_standing, "Colour by Relationship");
+        ui.checkbox(&mut self.plot.auto_fit, "Fit View When Switching Systems")
+            .on_hover_text("Otherwise the zoom and centre are kept, for comparing systems");
+
+        ui.separator();
+        ui.collapsing("Colours", |ui| self.plot.palette.edit(ui));
+
+        ui.separator();
+        ui.strong("Distances");
+        ui.horizontal(|ui| {
+            for units in Units::ALL {
+                ui.radio_value(&mut self.units, units, units.name());
+            }
+        });
+
+        ui.separator();
+        ui.strong("Names");
+        ui.horizontal(|ui| {
+            for names in NameStyle::ALL {
+                ui.radio_value(&mut self.names, names, names.name());
+            }
+        })
+        .response
+        .on_hover_text("Internal ids are shown where the save has them, for modding");
+
+        ui.separator();
+        ui.strong("Startup");
+        ui.checkbox(&mut self.open_last, "Open Last Save on Startup");
+        ui.checkbox(&mut self.follow_player, "Follow the Player")
+            .on_hover_text("Switch to the player's current system on loading or reloading a save");
+
+        ui.separator();
+        ui.strong("Reloading");
+        ui.horizontal(|ui| {
+            ui.label("Check for Changes");
+            ComboBox::from_id_source("_reload_interval")
+                .selected_text(interval_text(self.reload_interval))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.reload_interval, None, interval_text(None));
+                    for secs in RELOAD_INTERVALS {
+                        ui.selectable_value(
+                            &mut self.reload_interval,
+                            Some(secs),
+                            interval_text(Some(secs)),
+                        );
+                    }
+                });
+        })
+        .response
+        .on_hover_text("Reload the open save when the game writes over it");
+
+        let auto_export = &mut self.auto_export;
+        ui.checkbox(&mut auto_export.enabled, "Export on Reload")
+            .on_hover_text("Write every system to a file each time the save is reloaded");
+        ui.add_enabled_ui(auto_export.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("To");
+                ui.text_edit_singleline(&mut auto_export.path);
+            });
+            ui.horizontal(|ui| {
+                for format in ExportFormat::ALL {
+                    ui.radio_value(&mut auto_export.format, format, format.name());
+                }
+            });
+        });
+
+        ui.separator();
+        ui.strong("Loading");
+        ui.label("Planets are always read. Leaving out the rest speeds up enormous saves.");
+        ui.checkbox(&mut self.parsing.entities, "Read Entities")
+            .on_hover_text("Stations, relays, salvage and other objects that aren't planets");
+        ui.checkbox(&mut self.parsing.fleets, "Read Fleets")
+            .on_hover_text("The player's fleet is always read, to know where they are");
+        ui.checkbox(&mut self.parsing.terrain, "Read Terrain")
+            .on_hover_text("Nebulae, fields and rings");
+        ui.small("Takes effect the next time a save is loaded.");
+    }
+}
+
+/// How the system list is organised.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum Grouping {
+    #[default]
+    None,
+    Faction,
+    Region,
+}
+
+impl Grouping {
+    const ALL: [Grouping; 3] = [Grouping::None, Grouping::Faction, Grouping::Region];
+
+    fn name(self) -> &'static str {
+        match self {
+            Grouping::None => "Ungrouped",
+            Grouping::Faction => "By Faction",
+            Grouping::Region => "By Region",
+        }
+    }
+
+    /// The header `system` is listed under.
+    fn group(self, system: &System) -> &str {
+        match self {
+            Grouping::None => "",
+            Grouping::Faction => system
+                .controlling_faction()
+                .map_or("Unclaimed", faction_name),
+            Grouping::Region => system.region().unwrap_or("Unknown"),
+        }
+    }
+}
+
+/// Which name objects and systems are shown by.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum NameStyle {
+    /// The name the game shows.
+    #[default]
+    Friendly,
+    /// The id the game and mods use, for cross-referencing them.
+    Internal,
+}
+
+impl NameStyle {
+    const ALL: [NameStyle; 2] = [NameStyle::Friendly, NameStyle::Internal];
+
+    fn name(self) -> &'static str {
+        match self {
+            NameStyle::Friendly => "Friendly Names",
+            NameStyle::Internal => "Internal Ids",
+        }
+    }
+
+    /// The name to show for something with the given friendly name and internal id, keeping to
+    /// the friendly name where there's no id.
+    fn pick<'a>(self, name: &'a str, internal_id: Option<&'a str>) -> &'a str {
+        match self {
+            NameStyle::Friendly => name,
+            NameStyle::Internal => internal_id.unwrap_or(name),
+        }
+    }
+
+    fn object(self, object: &Object) -> &str {
+        self.pick(&object.name, object.internal_id.as_deref())
+    }
+
+    fn system(self, system: &System) -> &str {
+        self.pick(&system.name, system.internal_id.as_deref())
+    }
+}
+
+/// How hyperspace distances between systems are shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum Units {
+    GameUnits,
+    /// Light-years, at [`UNITS_PER_LIGHT_YEAR`] game units each as in the game's own readouts.
+    #[default]
+    LightYears,
+}
+
+impl Units {
+    const ALL: [Units; 2] = [Units::GameUnits, Units::LightYears];
+
+    fn name(self) -> &'static str {
+        match self {
+            Units::GameUnits => "Game Units",
+            Units::LightYears => "Light-years",
+        }
+    }
+
+    fn format(self, distance: f64) -> String {
+        match self {
+            Units::GameUnits => format!("{distance:.0} units"),
+            Units::LightYears => format!("{:.1} ly", distance / UNITS_PER_LIGHT_YEAR),
+        }
+    }
+}
+
+/// A campaign measure to chart across saves.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Metric {
+    #[default]
+    Credits,
+    Colonies,
+    ExploredSystems,
+}
+
+impl Metric {
+    const ALL: [Metric; 3] = [Metric::Credits, Metric::Colonies, Metric::ExploredSystems];
+
+    fn name(self) -> &'static str {
+        match self {
+            Metric::Credits => "Credits",
+            Metric::Colonies => "Colonies",
+            Metric::ExploredSystems => "Explored Systems",
+        }
+    }
+
+    fn value(self, progress: &Progress) -> Option<f64> {
+        match self {
+            Metric::Credits => progress.credits,
+            Metric::Colonies => Some(progress.colonies as f64),
+            Metric::ExploredSystems => Some(progress.explored_systems as f64),
+        }
+    }
+}
+
+type Measured = Vec<(PathBuf, Result<Progress, String>)>;
+
+/// A chart of campaign progress across several saves, ordered by save date.
+#[derive(Debug, Default)]
+struct Timeline {
+    open: bool,
+    metric: Metric,
+    saves: Vec<(PathBuf, Progress)>,
+    errors: Vec<String>,
+    pick_files: Option<JoinHandle<Measured>>,
+}
+
+impl Timeline {
+    fn show(&mut self, ctx: &egui::Context) {
+        use eframe::egui::plot::{Line, Plot, PlotPoints, Points};
+
+        if self.pick_files.as_ref().is_some_and(|t| t.is_finished()) {
+            for (path, progress) in self.pick_files.take().unwrap().join().unwrap() {
+                match progress {
+                    Ok(progress) => {
+                        self.saves.retain(|s| !same_path(&s.0, &path));
+                        self.saves.push((path, progress));
+                    }
+                    Err(e) => self.errors.push(format!("{}: {e}", path.display())),
+                }
+            }
+            self.saves
+                .sort_by_cached_key(|(_, p)| p.save_date.as_deref().map(date_order));
+        }
+
+        let mut open = self.open;
+        egui::Window::new("Campaign Timeline")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(self.pick_files.is_none(), |ui| {
+                        if ui.button("Add Saves").clicked() {
+                            let ctx = ctx.clone();
+                            // Saves are measured on the dialog's thread rather than blocking
+                            // the UI while several are read
+                            self.pick_files = Some(std::thread::spawn(move || {
+                                let paths = rfd::FileDialog::new()
+                                    .add_filter("Saves", &["xml", "gz"])
+                                    .pick_files()
+                                    .unwrap_or_default();
+                                let measured = paths
+                                    .into_iter()
+                                    .map(|path| {
+                                        let progress =
+                                            Progress::load(&path).map_err(|e| e.to_string());
+                                        (path, progress)
+                                    })
+                                    .collect();
+                                ctx.request_repaint();
+                                measured
+                            }));
+                        }
+                    });
+                    if self.pick_files.is_some() {
+                        ui.spinner();
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.saves.clear();
+                        self.errors.clear();
+                    }
+
+                    ComboBox::from_id_source("_timeline_metric")
+                        .selected_text(self.metric.name())
+                        .show_ui(ui, |ui| {
+                            for metric in Metric::ALL {
+                                ui.selectable_value(&mut self.metric, metric, metric.name());
+                            }
+                        });
+                });
+
+                for error in &self.errors {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                let points: Vec<[f64; 2]> = self
+                    .saves
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, (_, p))| Some([i as f64, self.metric.value(p)?]))
+                    .collect();
+                let dates: Vec<String> = self
+                    .saves
+                    .iter()
+                    .map(|(path, p)| {
+                        p.save_date
+                            .clone()
+                            .unwrap_or_else(|| path.display().to_string())
+                    })
+                    .collect();
+
+                Plot::new("timeline")
+                    .height(240.0)
+                    .include_y(0.0)
+                    .x_axis_formatter(move |x, _| {
+                        // Only label the whole numbers the saves sit at
+                        let index = x.round();
+                        if (x - index).abs() > f64::EPSILON || index < 0.0 {
+                            return String::new();
+                        }
+                        dates.get(index as usize).cloned().unwrap_or_default()
+                    })
+                    .show(ui, |plot_ui| {
+                        plot_ui.line(Line::new(PlotPoints::from(points.clone())));
+                        plot_ui.points(Points::new(PlotPoints::from(points)).radius(4.0));
+                    });
+            });
+        self.open = open;
+    }
+}
+
+/// What an export covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportScope {
+    All,
+    Current,
+    /// A Markdown summary of the whole save.
+    Report,
+    /// A Markdown table of the best colony candidates in the save.
+    Candidates,
+    /// Positions of the current system's objects mapped to pixels of an image.
+    Overlay,
+    /// The systems marked in the system list, as JSON.
+    Marked,
+    /// The objects of the systems marked in the system list, as CSV.
+    MarkedCsv,
+    /// The systems marked in the system list, as a PNG sheet of summary cards.
+    MarkedSheet,
+    /// The objects of every system, as CSV.
+    AllCsv,
+    /// The systems passing the current filters, as JSON.
+    Filtered,
+    /// The objects of the systems passing the current filters, as CSV.
+    FilteredCsv,
+    /// A Graphviz graph of the systems joined by gates.
+    GateNetwork,
+}
+
+/// Restricts the system list by the black holes and pulsars in them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum HazardFilter {
+    #[default]
+    Any,
+    /// Systems with neither.
+    Avoid,
+    BlackHole,
+    Pulsar,
+}
+
+impl HazardFilter {
+    const ALL: [HazardFilter; 4] = [
+        HazardFilter::Any,
+        HazardFilter::Avoid,
+        HazardFilter::BlackHole,
+        HazardFilter::Pulsar,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            HazardFilter::Any => "Any",
+            HazardFilter::Avoid => "None",
+            HazardFilter::BlackHole => StellarHazard::BlackHole.name(),
+            HazardFilter::Pulsar => StellarHazard::Pulsar.name(),
+        }
+    }
+
+    fn matches(self, system: &System) -> bool {
+        let hazards = system.stellar_hazards();
+        match self {
+            HazardFilter::Any => true,
+            HazardFilter::Avoid => hazards.is_empty(),
+            HazardFilter::BlackHole => hazards.contains(&StellarHazard::BlackHole),
+            HazardFilter::Pulsar => hazards.contains(&StellarHazard::Pulsar),
+        }
+    }
+}
+
+/// Whether systems must have all, any or none of the chosen resources.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum Combine {
+    #[default]
+    All,
+    Any,
+    /// None of them, for finding systems that would have to import a resource.
+    Missing,
+}
+
+impl Combine {
+    const ALL: [Combine; 3] = [Combine::All, Combine::Any, Combine::Missing];
+
+    fn name(self) -> &'static str {
+        match self {
+            Combine::All => "All",
+            Combine::Any => "Any",
+            Combine::Missing => "Missing",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Combine::All => "Systems with every chosen resource",
+            Combine::Any => "Systems with at least one chosen resource",
+            Combine::Missing => "Systems where nothing provides any chosen resource",
+        }
+    }
+}
+
+/// Which supplies of a resource count towards a system having it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum SupplyFilter {
+    /// Deposits a colony can keep mining.
+    #[default]
+    Sustained,
+    /// Caches to be salvaged the once.
+    OneTime,
+    Either,
+}
+
+impl SupplyFilter {
+    const ALL: [SupplyFilter; 3] = [
+        SupplyFilter::Sustained,
+        SupplyFilter::OneTime,
+        SupplyFilter::Either,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            SupplyFilter::Sustained => "Sustained",
+            SupplyFilter::OneTime => "One-time",
+            SupplyFilter::Either => "Either",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            SupplyFilter::Sustained => "Count deposits a colony can keep mining",
+            SupplyFilter::OneTime => "Count caches to be salvaged once",
+            SupplyFilter::Either => "Count deposits and caches alike",
+        }
+    }
+
+    fn accepts(self, system: &System, resource: Resource) -> bool {
+        match self {
+            SupplyFilter::Sustained => system.supplies(resource, Supply::Sustained),
+            SupplyFilter::OneTime => system.supplies(resource, Supply::OneTime),
+            SupplyFilter::Either => Supply::ALL
+                .into_iter()
+                .any(|supply| system.supplies(resource, supply)),
+        }
+    }
+}
+
+/// Restricts the system list to systems with, or without, chosen resources.
+#[derive(Debug, Default)]
+struct ResourceFilter {
+    wanted: BTreeSet<Resource>,
+    combine: Combine,
+    supply: SupplyFilter,
+}
+
+impl ResourceFilter {
+    fn matches(&self, system: &System) -> bool {
+        if self.wanted.is_empty() {
+            return true;
+        }
+
+        let mut present = self
+            .wanted
+            .iter()
+            .map(|&resource| self.supply.accepts(system, resource));
+
+        match self.combine {
+            Combine::All => present.all(|p| p),
+            Combine::Any => present.any(|p| p),
+            Combine::Missing => !present.any(|p| p),
+        }
+    }
+
+    fn edit(&mut self, ui: &mut Ui) {
+        ui.label("Resources");
+        for resource in Resource::ALL {
+            let mut wanted = self.wanted.contains(&resource);
+            if ui.checkbox(&mut wanted, resource.name()).changed() {
+                if wanted {
+                    self.wanted.insert(resource);
+                } else {
+                    self.wanted.remove(&resource);
+                }
+            }
+        }
+
+        for combine in Combine::ALL {
+            ui.selectable_value(&mut self.combine, combine, combine.name())
+                .on_hover_text(combine.description());
+        }
+
+        ui.separator();
+        for supply in SupplyFilter::ALL {
+            ui.selectable_value(&mut self.supply, supply, supply.name())
+                .on_hover_text(supply.description());
+        }
+    }
+}
+
+/// Search for the closest systems to the selected one with a given resource.
+#[derive(Debug)]
+struct NearestQuery {
+    resource: Resource,
+    min_grade: u8,
+}
+
+impl Default for NearestQuery {
+    fn default() -> Self {
+        Self {
+            resource: Resource::Ore,
+            min_grade: 1,
+        }
+    }
+}
+
+/// The sizes colonies come in, from a fresh outpost up.
+const MARKET_SIZES: std::ops::RangeInclusive<u8> = 3..=10;
+
+/// How many planets the colony candidates export ranks.
+const CANDIDATE_LIMIT: usize = 20;
+
+/// How many object search results are listed per page.
+const MATCHES_PER_PAGE: usize = 200;
+
+/// How many recently opened saves are remembered.
+const RECENT_LIMIT: usize = 10;
+
+/// What's kept of the view of one save while others are open, so it can be picked up again.
+/// Types from the library are stored by name.
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+struct SaveState {
+    selected: Option<String>,
+    notes: BTreeMap<String, String>,
+    filter: String,
+    use_regex: bool,
+    search_scope: SearchScope,
+    mission_only: bool,
+    remnants_only: bool,
+    solar_only: bool,
+    ports_only: bool,
+    free_ports_only: bool,
+    blueprints_only: bool,
+    uncolonized_only: bool,
+    stable_location_only: bool,
+    belts_only: bool,
+    hand_authored_only: bool,
+    missing_infrastructure_only: bool,
+    domain_array_only: bool,
+    weakly_defended_only: bool,
+    quick_filters: Vec<QuickFilter>,
+    shortage: Option<String>,
+    industry: Option<String>,
+    mission_faction: Option<String>,
+    region: Option<String>,
+    resources: Vec<String>,
+    combine: Combine,
+    supply: SupplyFilter,
+    min_colony_size: Option<String>,
+    min_market_size: Option<u8>,
+    atmosphere: Option<String>,
+    temperature: Option<String>,
+    multiplicity: Option<String>,
+    min_danger: Option<String>,
+    stellar_hazard: HazardFilter,
+    grouping: Grouping,
+    open_groups: Vec<String>,
+}
+
+/// The value among `all` going by `name`, as stored in a [`SaveState`].
+fn by_name<T: Copy>(all: &[T], name: Option<&str>, name_of: fn(T) -> &'static str) -> Option<T> {
+    all.iter()
+        .copied()
+        .find(|&value| Some(name_of(value)) == name)
+}
+
+/// The key a save's state is stored under, the same for any spelling [`same_path`] accepts.
+fn path_key(path: &Path) -> String {
+    let key = path.to_string_lossy();
+    if cfg!(any(windows, target_os = "macos")) {
+        key.to_lowercase()
+    } else {
+        key.into_owned()
+    }
+}
+
+/// Whether two paths name the same file, ignoring case on platforms whose filesystems
+/// usually do, so a save opened as `Save.xml` and `save.xml` isn't treated as two.
+fn same_path(a: &Path, b: &Path) -> bool {
+    if cfg!(any(windows, target_os = "macos")) {
+        a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// A column of the object table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    #[default]
+    Name,
+    Type,
+    Position,
+    Conditions,
+    Faction,
+    MarketSize,
+    Mission,
+}
+
+impl Column {
+    const ALL: [Column; 7] = [
+        Column::Name,
+        Column::Type,
+        Column::Position,
+        Column::Conditions,
+        Column::Faction,
+        Column::MarketSize,
+        Column::Mission,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Column::Name => "Name",
+            Column::Type => "Type",
+            Column::Position => "Position",
+            Column::Conditions => "Conditions",
+            Column::Faction => "Faction",
+            Column::MarketSize => "Market Size",
+            Column::Mission => "Mission",
+        }
+    }
+
+    fn compare(self, a: &Object, b: &Object) -> std::cmp::Ordering {
+        match self {
+            Column::Name => a.name.cmp(&b.name),
+            Column::Type => object_type(a).cmp(object_type(b)),
+            // Nearest the centre of the system first
+            Column::Position => {
+                let origin = |o: &Object| o.pos.x.hypot(o.pos.y);
+                origin(a).total_cmp(&origin(b))
+            }
+            Column::Conditions => conditions(a).cmp(&conditions(b)),
+            Column::Faction => {
+                let (a, b) = (a.faction.as_deref(), b.faction.as_deref());
+                a.map(faction_name).cmp(&b.map(faction_name))
+            }
+            Column::MarketSize => a.market_size().cmp(&b.market_size()),
+            Column::Mission => a.mission.cmp(&b.mission),
+        }
+    }
+}
+
+fn object_type(object: &Object) -> &str {
+    object.planet_type.as_deref().unwrap_or(match object.kind {
+        Kind::Planet => "planet",
+        Kind::Entity => "entity",
+    })
+}
+
+fn conditions(object: &Object) -> String {
+    object
+        .market
+        .as_ref()
+        .map(|m| m.conditions.join(", "))
+        .unwrap_or_default()
+}
+
+/// Sorting and filtering of the object table.
+#[derive(Debug, Default)]
+struct ObjectTable {
+    show: bool,
+    sort: Column,
+    descending: bool,
+    filter: String,
+}
+
+/// Browser-style back and forward history of selected systems.
+#[derive(Debug, Default)]
+struct History {
+    back: Vec<usize>,
+    forward: Vec<usize>,
+}
+
+impl History {
+    const LIMIT: usize = 100;
+
+    fn visit(&mut self, from: usize) {
+        if self.back.len() == Self::LIMIT {
+            self.back.remove(0);
+        }
+        self.back.push(from);
+        self.forward.clear();
+    }
+
+    fn back(&mut self, current: usize) -> Option<usize> {
+        let previous = self.back.pop()?;
+        self.forward.push(current);
+        Some(previous)
+    }
+
+    fn forward(&mut self, current: usize) -> Option<usize> {
+        let next = self.forward.pop()?;
+        self.back.push(current);
+        Some(next)
+    }
+
+    fn clear(&mut self) {
+        self.back.clear();
+        self.forward.clear();
+    }
+
+    /// Move each system visited to where `to` says it is now, forgetting those it's lost.
+    fn remap(&mut self, mut to: impl FnMut(usize) -> Option<usize>) {
+        self.back = self.back.iter().filter_map(|&s| to(s)).collect();
+        self.forward = self.forward.iter().filter_map(|&s| to(s)).collect();
+        self.back.dedup();
+        self.forward.dedup();
+    }
+}
+
+#[derive(Debug, Default)]
+struct ScanSectorUi {
+    pick_file: Option<JoinHandle<Option<PathBuf>>>,
+    pick_folder: Option<JoinHandle<Option<std::io::Result<Vec<Campaign>>>>>,
+    /// Campaigns found in the last saves folder picked, to switch between.
+    campaigns: Vec<Campaign>,
+    export_file: Option<(ExportScope, JoinHandle<Option<PathBuf>>)>,
+    message: Option<String>,
+    warning: Option<String>,
+    status: Option<String>,
+    save: Option<PathBuf>,
+    /// When the open save was last written, as of loading it.
+    save_modified: Option<SystemTime>,
+    /// The modification time seen on the last check for changes, and when that was.
+    last_check: Option<(Option<SystemTime>, Instant)>,
+    systems: Vec<System>,
+    filter: NameFilter,
+    search_scope: SearchScope,
+    mission_only: bool,
+    remnants_only: bool,
+    solar_only: bool,
+    ports_only: bool,
+    free_ports_only: bool,
+    blueprints_only: bool,
+    uncolonized_only: bool,
+    stable_location_only: bool,
+    belts_only: bool,
+    hand_authored_only: bool,
+    /// Whether the save tells generated systems from hand-authored ones, for
+    /// [`ScanSectorUi::hand_authored_only`].
+    marks_procgen: bool,
+    missing_infrastructure_only: bool,
+    domain_array_only: bool,
+    weakly_defended_only: bool,
+    quick_filters: HashSet<QuickFilter>,
+    visibility: Visibility,
+    shortage: Option<String>,
+    /// The name of an industry or structure systems must have a colony with, covering every
+    /// tier that shares it.
+    industry: Option<String>,
+    /// A faction systems must have missions from.
+    mission_faction: Option<String>,
+    /// The constellation or quadrant systems must be in.
+    region: Option<String>,
+    resources: ResourceFilter,
+    min_colony_size: Option<Size>,
+    min_market_size: Option<u8>,
+    atmosphere: Option<Atmosphere>,
+    temperature: Option<Temperature>,
+    multiplicity: Option<Multiplicity>,
+    /// The least Remnant danger to show systems with.
+    min_danger: Option<Danger>,
+    stellar_hazard: HazardFilter,
+    nearest: NearestQuery,
+    selected: usize,
+    selected_object: Option<usize>,
+    /// An object whose details stay shown for reference, as `(system, object)` indices.
+    pinned: Option<(usize, usize)>,
+    /// Systems marked for export by ctrl-clicking them in the system list.
+    marked: HashSet<usize>,
+    history: History,
+    plot_state: PlotState,
+    /// Favourite objects.
+    favorites: BTreeSet<ObjectKey>,
+    /// The user's notes on the open save's systems, by system name.
+    notes: BTreeMap<String, String>,
+    /// The view of every other save opened, by [`path_key`].
+    saves: BTreeMap<String, SaveState>,
+    /// A system to select on loading, restored from the save's state.
+    restore_selected: Option<String>,
+    grouping: Grouping,
+    /// The system list groups shown expanded, by header.
+    open_groups: BTreeSet<String>,
+    table: ObjectTable,
+    descriptor: Descriptor,
+    relationships: BTreeMap<String, f64>,
+    /// The image resolution overlay exports are mapped to.
+    overlay_size: (u32, u32),
+    /// Label gate network exports with the distances between systems.
+    weight_gates: bool,
+    settings: Settings,
+    show_settings: bool,
+    show_help: bool,
+    confirm_reset: bool,
+    /// Show the whole sector rather than the selected system.
+    sector_view: bool,
+    timeline: Timeline,
+    /// Recently opened saves, most recent first.
+    recent: Vec<PathBuf>,
+    /// Sector-wide lists derived from `systems`, kept from load to save working them out on
+    /// every frame.
+    shortages: Vec<String>,
+    /// Every industry and structure built in the sector, by name.
+    industries: Vec<String>,
+    /// Every faction offering missions in the sector, by id.
+    mission_factions: Vec<String>,
+    /// Every region systems are in, as [`System::region`] has them.
+    regions: Vec<String>,
+    /// Fully surveyed planets, out of those with a survey level.
+    survey_progress: (usize, usize),
+    home_worlds: Vec<(usize, usize, f64)>,
+    /// Systems never visited, with their distance from the player.
+    unvisited: Vec<(usize, Option<f64>)>,
+    uniques: Vec<(usize, usize, Unique)>,
+    /// Objects whose names pass the filter, by system and object, when searching objects.
+    match_hits: Vec<(usize, usize)>,
+    /// The page of object search results being shown.
+    match_page: usize,
+    anomalies: Vec<Vec<Anomaly>>,
+    /// Links from the player's colonies to the nearest markets, as [`trade_links`] has them.
+    trade_links: Vec<(usize, usize)>,
+    /// Where storms were on the go in hyperspace, as the save has them.
+    storms: Vec<StormCell>,
+    /// How far each system is from the nearest storm, as [`System::storm_distance`] has it.
+    storm_distances: Vec<Option<f64>>,
+    /// How many systems each faction controls, most first.
+    territory: Vec<(String, usize)>,
+    /// What's different since the save was last loaded, by system, as [`changes`] has it.
+    changes: BTreeMap<String, Vec<ObjectChange>>,
+    /// When the save was last reloaded, to fade out the highlighted changes.
+    changed_at: Option<Instant>,
+}
+
+impl ScanSectorUi {
+    fn new(cc: &eframe::CreationContext<'_>, args: Args) -> Self {
+        let mut ui = Self {
+            overlay_size: (1920, 1080),
+            ..Self::default()
+        };
+        if let Some(storage) = cc.storage {
+            ui.settings = Settings::load(storage);
+            ui.favorites = eframe::get_value(storage, "favorites").unwrap_or_default();
+            ui.saves = eframe::get_value(storage, "saves").unwrap_or_default();
+            ui.recent = eframe::get_value(storage, "recent").unwrap_or_default();
+        }
+
+        if let Some(save) = args.save {
+            // A failed load's error stays up, rather than a complaint about the missing system
+            if ui.load(save) {
+                if let Some(system) = args.system {
+                    ui.select_system(&system);
+                }
+            }
+        } else if let Some(last) = ui.recent.first().filter(|_| ui.settings.open_last).cloned() {
+            ui.load(last);
+            // Leave it to the user to pick something else if the last save has gone
+            if ui.message.is_some() {
+                ui.message = None;
+                ui.warning = None;
+                ui.save = None;
+            }
+        }
+        ui
+    }
+
+    /// Keep the selection within the loaded systems and their objects, so indexing with it
+    /// can't panic whatever has changed since it was made.
+    fn clamp_selection(&mut self) {
+        self.selected = self.selected.min(self.systems.len().saturating_sub(1));
+        let objects = self
+            .systems
+            .get(self.selected)
+            .map_or(0, |s| s.objects.len());
+        self.selected_object = self.selected_object.filter(|&i| i < objects);
+        self.pinned = self
+            .pinned
+            .filter(|&(s, o)| self.systems.get(s).is_some_and(|s| o < s.objects.len()));
+    }
+
+    /// A snapshot of the open save's selection, filters and notes.
+    fn save_state(&self) -> SaveState {
+        SaveState {
+            selected: self.systems.get(self.selected).map(|s| s.name.clone()),
+            notes: self.notes.clone(),
+            filter: self.filter.text.clone(),
+            use_regex: self.filter.use_regex,
+            search_scope: self.search_scope,
+            mission_only: self.mission_only,
+            remnants_only: self.remnants_only,
+            solar_only: self.solar_only,
+            ports_only: self.ports_only,
+            free_ports_only: self.free_ports_only,
+            blueprints_only: self.blueprints_only,
+            uncolonized_only: self.uncolonized_only,
+            stable_location_only: self.stable_location_only,
+            belts_only: self.belts_only,
+            hand_authored_only: self.hand_authored_only,
+            missing_infrastructure_only: self.missing_infrastructure_only,
+            domain_array_only: self.domain_array_only,
+            weakly_defended_only: self.weakly_defended_only,
+            quick_filters: QuickFilter::ALL
+                .into_iter()
+                .filter(|f| self.quick_filters.contains(f))
+                .collect(),
+            shortage: self.shortage.clone(),
+            industry: self.industry.clone(),
+            mission_faction: self.mission_faction.clone(),
+            region: self.region.clone(),
+            resources: self
+                .resources
+                .wanted
+                .iter()
+                .map(|r| r.name().to_string())
+                .collect(),
+            combine: self.resources.combine,
+            supply: self.resources.supply,
+            min_colony_size: self.min_colony_size.map(|s| s.name().to_string()),
+            min_market_size: self.min_market_size,
+            atmosphere: self.atmosphere.map(|a| a.name().to_string()),
+            temperature: self.temperature.map(|t| t.name().to_string()),
+            multiplicity: self.multiplicity.map(|m| m.name().to_string()),
+            min_danger: self.min_danger.map(|d| d.name().to_string()),
+            stellar_hazard: self.stellar_hazard,
+            grouping: self.grouping,
+            open_groups: self.open_groups.iter().cloned().collect(),
+        }
+    }
+
+    fn apply_save_state(&mut self, state: SaveState) {
+        self.restore_selected = state.selected;
+        self.notes = state.notes;
+        self.filter.text = state.filter;
+        self.filter.use_regex = state.use_regex;
+        self.filter.update();
+        self.search_scope = state.search_scope;
+        self.update_matches();
+        self.mission_only = state.mission_only;
+        self.remnants_only = state.remnants_only;
+        self.solar_only = state.solar_only;
+        self.ports_only = state.ports_only;
+        self.free_ports_only = state.free_ports_only;
+        self.blueprints_only = state.blueprints_only;
+        self.uncolonized_only = state.uncolonized_only;
+        self.stable_location_only = state.stable_location_only;
+        self.belts_only = state.belts_only;
+        self.hand_authored_only = state.hand_authored_only;
+        self.missing_infrastructure_only = state.missing_infrastructure_only;
+        self.domain_array_only = state.domain_array_only;
+        self.weakly_defended_only = state.weakly_defended_only;
+        self.quick_filters = state.quick_filters.into_iter().collect();
+        self.shortage = state.shortage;
+        self.industry = state.industry;
+        self.mission_faction = state.mission_faction;
+        self.region = state.region;
+        self.resources.wanted = state
+            .resources
+            .iter()
+            .filter_map(|name| by_name(&Resource::ALL, Some(name), Resource::name))
+            .collect();
+        self.resources.combine = state.combine;
+        self.resources.supply = state.supply;
+        self.min_colony_size = by_name(&Size::ALL, state.min_colony_size.as_deref(), Size::name);
+        self.min_market_size = state.min_market_size;
+        self.atmosphere = by_name(
+            &Atmosphere::ALL,
+            state.atmosphere.as_deref(),
+            Atmosphere::name,
+        );
+        self.temperature = by_name(
+            &Temperature::ALL,
+            state.temperature.as_deref(),
+            Temperature::name,
+        );
+        self.multiplicity = by_name(
+            &Multiplicity::ALL,
+            state.multiplicity.as_deref(),
+            Multiplicity::name,
+        );
+        self.min_danger = by_name(&Danger::ALL, state.min_danger.as_deref(), Danger::name);
+        self.stellar_hazard = state.stellar_hazard;
+        self.grouping = state.grouping;
+        self.open_groups = state.open_groups.into_iter().collect();
+    }
+
+    /// Put the open save's state aside and pick up where `path` was left, if it's been open
+    /// before.
+    fn switch_save_state(&mut self, path: &Path) {
+        if let Some(current) = &self.save {
+            let state = self.save_state();
+            self.saves.insert(path_key(current), state);
+        }
+        let state = self.saves.remove(&path_key(path)).unwrap_or_default();
+        self.apply_save_state(state);
+    }
+
+    /// Find the objects passing the filter again, from the first page, after the filter, the
+    /// search scope or the systems change.
+    fn update_matches(&mut self) {
+        self.match_page = 0;
+        self.match_hits.clear();
+        if self.search_scope != SearchScope::Objects || self.filter.is_empty() {
+            return;
+        }
+        for (s, system) in self.systems.iter().enumerate() {
+            for (o, object) in system.objects() {
+                if self.filter.matches(&object.name.to_lowercase()) {
+                    self.match_hits.push((s, o));
+                }
+            }
+        }
+    }
+
+    /// Copies of the systems passing the current filters.
+    fn filtered_systems(&self) -> Vec<System> {
+        self.systems
+            .iter()
+            .filter(|s| self.system_matches(s))
+            .cloned()
+            .collect()
+    }
+
+    fn select_system(&mut self, name: &str) {
+        let lc_name = name.to_lowercase();
+        match self.systems.iter().position(|s| s.lc_name == lc_name) {
+            Some(index) => self.selected = index,
+            None => self.message = Some(format!("No system named {name} in this save")),
+        }
+    }
+
+    fn export(&mut self, scope: ExportScope, path: &Path) {
+        let marked: Vec<System>;
+        let filtered: Vec<System>;
+        let systems = match scope {
+            ExportScope::All
+            | ExportScope::AllCsv
+            | ExportScope::Report
+            | ExportScope::Candidates
+            | ExportScope::GateNetwork => &self.systems[..],
+            ExportScope::Current | ExportScope::Overlay => {
+                std::slice::from_ref(&self.systems[self.selected])
+            }
+            ExportScope::Marked | ExportScope::MarkedCsv | ExportScope::MarkedSheet => {
+                let mut indices: Vec<usize> = self.marked.iter().copied().collect();
+                indices.sort_unstable();
+                marked = indices
+                    .into_iter()
+                    .map(|i| self.systems[i].clone())
+                    .collect();
+                &marked[..]
+            }
+            ExportScope::Filtered | ExportScope::FilteredCsv => {
+                filtered = self.filtered_systems();
+                &filtered[..]
+            }
+        };
+        let contents: Vec<u8> = match scope {
+            ExportScope::Report => {
+                report::to_markdown(&self.descriptor, systems, &self.relationships).into()
+            }
+            ExportScope::Candidates => report::colony_table(systems, CANDIDATE_LIMIT).into(),
+            ExportScope::Overlay => {
+                let (width, height) = self.overlay_size;
+                let system = &systems[0];
+                let transform = export::ImageTransform::fit(system, MIN_PADDING, width, height);
+                export::to_overlay_json(system, &transform).into()
+            }
+            ExportScope::All
+            | ExportScope::Current
+            | ExportScope::Marked
+            | ExportScope::Filtered => export::to_json(systems).into(),
+            ExportScope::AllCsv | ExportScope::MarkedCsv | ExportScope::FilteredCsv => {
+                export::to_csv(systems).into()
+            }
+            ExportScope::GateNetwork => export::to_dot(systems, self.weight_gates).into(),
+            ExportScope::MarkedSheet => {
+                let options = &self.settings.plot;
+                match sheet::to_png(systems, |object| options.color(object)) {
+                    Ok(png) => png,
+                    Err(e) => {
+                        self.message = Some(format!("Export failed: {e}"));
+                        return;
+                    }
+                }
+            }
+        };
+
+        match std::fs::write(path, contents) {
+            Ok(()) => {
+                self.status = Some(format!(
+                    "Exported {} systems to {}",
+                    systems.len(),
+                    path.display()
+                ));
+                self.message = None;
+            }
+            Err(e) => self.message = Some(format!("Export failed: {e}")),
+        }
+    }
+
+    /// Open the save at `path`, returning whether it could be read.
+    fn load(&mut self, path: PathBuf) -> bool {
+        // Reloading keeps everything as it is, but another save brings back its own view
+        let reloading = self.save.as_deref().is_some_and(|s| same_path(s, &path));
+        let descriptor = load_descriptor(&path).unwrap_or_default();
+        let mut warning = Some(&descriptor)
+            .filter(|d| d.untested_version())
+            .and_then(|d| d.game_version.as_ref())
+            .map(|version| {
+                format!(
+                    "This save is from Starsector {version}, which hasn't been tested. \
+                     Some systems or objects may be missing."
+                )
+            });
+
+        let start = Instant::now();
+        let loaded = match load_sector_with(&path, self.settings.parsing.options()) {
+            Ok(Sector {
+                systems,
+                relationships,
+                problems,
+                storms,
+            }) => {
+                // Only now the save's known to be readable is the one being left put aside
+                if !reloading {
+                    self.switch_save_state(&path);
+                }
+                if let Some(first) = problems.first() {
+                    let problems = format!(
+                        "Parts of this save couldn't be read. {first}{}",
+                        match problems.len() {
+                            1 => String::new(),
+                            n => format!(" (and {} more)", n - 1),
+                        }
+                    );
+                    warning = Some(match warning.take() {
+                        Some(warning) => format!("{warning}\n{problems}"),
+                        None => problems,
+                    });
+                }
+                self.descriptor = descriptor;
+                self.warning = warning;
+                self.status = Some(format!(
+                    "Loaded {} systems with {} objects in {:.2?}",
+                    systems.len(),
+                    all_objects(&systems).count(),
+                    start.elapsed()
+                ));
+                let mut shortages: Vec<String> = all_objects(&systems)
+                    .filter_map(|(_, o)| o.market.as_ref())
+                    .flat_map(|m| m.deficits())
+                    .map(|c| c.id.clone())
+                    .collect();
+                shortages.sort_unstable();
+                shortages.dedup();
+                self.shortages = shortages;
+                // Tiers of a station share a name, and one entry covers them all
+                let mut industries: Vec<String> = all_objects(&systems)
+                    .filter_map(|(_, o)| o.market.as_ref())
+                    .flat_map(|m| m.industries.iter().map(|i| industry_name(i).to_string()))
+                    .collect();
+                industries.sort_unstable();
+                industries.dedup();
+                self.industries = industries;
+                let mut mission_factions: Vec<String> = systems
+                    .iter()
+                    .flat_map(|s| s.mission_factions())
+                    .map(str::to_string)
+                    .collect();
+                mission_factions
+                    .sort_unstable_by(|a, b| (faction_name(a), a).cmp(&(faction_name(b), b)));
+                mission_factions.dedup();
+                self.mission_factions = mission_factions;
+                let mut regions: Vec<String> = systems
+                    .iter()
+                    .filter_map(System::region)
+                    .map(str::to_string)
+                    .collect();
+                regions.sort_unstable();
+                regions.dedup();
+                self.regions = regions;
+                self.home_worlds = home_world_candidates(&systems);
+                self.unvisited = unvisited_systems(&systems);
+                self.uniques = unique_structures(&systems);
+                self.marks_procgen = marks_procgen(&systems);
+                self.territory = territory(&systems)
+                    .into_iter()
+                    .map(|(faction, count)| (faction.to_string(), count))
+                    .collect();
+                self.anomalies = anomalies(&systems);
+                self.trade_links = trade_links(&systems, TRADE_LINKS_PER_COLONY);
+                self.storm_distances = systems.iter().map(|s| s.storm_distance(&storms)).collect();
+                self.storms = storms;
+                self.survey_progress = survey_progress(&systems);
+
+                // Stay on the same system when reloading a save, where it's still there
+                let was_selected = self.selected;
+                let previous = self
+                    .restore_selected
+                    .take()
+                    .or_else(|| self.systems.get(self.selected).map(|s| s.name.clone()));
+                let player = systems
+                    .iter()
+                    .position(|s| s.has_player())
+                    .filter(|_| self.settings.follow_player);
+                self.selected = player
+                    .or_else(|| {
+                        previous.and_then(|name| systems.iter().position(|s| s.name == name))
+                    })
+                    .unwrap_or(0);
+                self.changes = if reloading {
+                    changes(&self.systems, &systems)
+                } else {
+                    BTreeMap::new()
+                };
+                self.changed_at = Some(Instant::now());
+                if reloading {
+                    // Pins, marks and history follow their systems and objects to where they
+                    // are now, and are let go of where those have gone
+                    let old = &self.systems;
+                    let system = |index: usize| {
+                        let name = &old.get(index)?.name;
+                        systems.iter().position(|s| s.name == *name)
+                    };
+                    let object = |(s, o): (usize, usize)| {
+                        let (key, nth) = old.get(s).filter(|s| o < s.objects.len())?.object_key(o);
+                        let s = system(s)?;
+                        Some((s, systems[s].find_object(key, nth)?))
+                    };
+                    self.selected_object = self
+                        .selected_object
+                        .and_then(|o| object((was_selected, o)))
+                        .filter(|&(s, _)| s == self.selected)
+                        .map(|(_, o)| o);
+                    self.pinned = self.pinned.and_then(object);
+                    self.marked = self.marked.iter().filter_map(|&s| system(s)).collect();
+                    self.history.remap(system);
+                } else {
+                    self.selected_object = None;
+                    self.pinned = None;
+                    self.marked.clear();
+                    self.history.clear();
+                }
+                self.systems = systems;
+                self.update_matches();
+                self.relationships = relationships;
+                self.message = None;
+
+                self.recent.retain(|recent| !same_path(recent, &path));
+                self.recent.insert(0, path.clone());
+                self.recent.truncate(RECENT_LIMIT);
+                true
+            }
+            Err(e) => {
+                self.message = Some(e.to_string());
+                self.status = None;
+                false
+            }
+        };
+        // A save that won't open leaves the one open as it was, but a reload that fails waits
+        // for the save to change again before trying once more
+        if loaded || reloading {
+            self.save_modified = modified(&path);
+            self.last_check = Some((self.save_modified, Instant::now()));
+            self.save = Some(path);
+        }
+        loaded
+    }
+
+    /// Write the export the settings ask for after a reload, if any.
+    fn auto_export(&mut self) {
+        let auto_export = &self.settings.auto_export;
+        if !auto_export.enabled || auto_export.path.trim().is_empty() {
+            return;
+        }
+        let path = PathBuf::from(auto_export.path.trim());
+        self.export(auto_export.format.scope(), &path);
+    }
+
+    /// The open save, if it's been written over since it was loaded and is due a check.  Changes
+    /// must hold for a whole interval before counting, so as not to read a save the game is still
+    /// in the middle of writing.
+    fn changed_save(&mut self, ctx: &egui::Context) -> Option<PathBuf> {
+        let interval = Duration::from_secs(self.settings.reload_interval?);
+        let path = self.save.clone()?;
+        ctx.request_repaint_after(interval);
+
+        let (seen, checked) = self.last_check?;
+        if checked.elapsed() < interval {
+            return None;
+        }
+        let now = modified(&path);
+        self.last_check = Some((now, Instant::now()));
+        (now.is_some() && now == seen && now != self.save_modified).then_some(path)
+    }
+
+    /// The setting behind the checkbox for `filter`.
+    fn toggle_filter(&mut self, filter: ToggleFilter) -> &mut bool {
+        match filter {
+            ToggleFilter::Mission => &mut self.mission_only,
+            ToggleFilter::RemnantFleets => &mut self.remnants_only,
+            ToggleFilter::Port => &mut self.ports_only,
+            ToggleFilter::FreePort => &mut self.free_ports_only,
+            ToggleFilter::Blueprints => &mut self.blueprints_only,
+            ToggleFilter::Uncolonized => &mut self.uncolonized_only,
+            ToggleFilter::StableLocation => &mut self.stable_location_only,
+            ToggleFilter::AsteroidBelts => &mut self.belts_only,
+            ToggleFilter::HandAuthored => &mut self.hand_authored_only,
+            ToggleFilter::MissingInfrastructure => &mut self.missing_infrastructure_only,
+            ToggleFilter::DomainArray => &mut self.domain_array_only,
+            ToggleFilter::SoftTargets => &mut self.weakly_defended_only,
+            ToggleFilter::Solar => &mut self.solar_only,
+        }
+    }
+
+    fn system_matches(&self, system: &System) -> bool {
+        let shortage = self.shortage.as_ref().is_none_or(|commodity| {
+            system
+                .objects
+                .iter()
+                .filter_map(|o| o.market.as_ref())
+                .any(|m| m.deficits().any(|c| &c.id == commodity))
+        });
+
+        let industry = self.industry.as_deref().is_none_or(|industry| {
+            system
+                .objects
+                .iter()
+                .filter_map(|o| o.market.as_ref())
+                .any(|m| m.industries.iter().any(|i| industry_name(i) == industry))
+        });
+
+        (!self.mission_only || system.mission)
+            && (!self.remnants_only || system.remnant_fleets() > 0)
+            && (!self.solar_only || system.solar_power().is_some_and(|p| p.viable()))
+            && (!self.ports_only || system.port().is_some())
+            && (!self.free_ports_only || system.has_free_port())
+            && (!self.blueprints_only || system.objects.iter().any(|o| o.has_blueprints()))
+            && (!self.uncolonized_only || system.uncolonized())
+            && (!self.stable_location_only || system.free_stable_locations() > 0)
+            && (!self.belts_only || system.has_asteroid_belt())
+            && (!self.hand_authored_only || !self.marks_procgen || !system.procgen)
+            && (!self.missing_infrastructure_only || !system.missing_infrastructure().is_empty())
+            && (!self.domain_array_only || system.domain_sensor_arrays().next().is_some())
+            && (!self.weakly_defended_only
+                || system.objects.iter().any(|o| {
+                    o.faction.as_deref() != Some(PLAYER_FACTION)
+                        && o.market
+                            .as_ref()
+                            .and_then(Market::defense)
+                            .is_some_and(Defense::weak)
+                }))
+            && self.quick_filters.iter().all(|f| f.matches(system))
+            && (self.search_scope == SearchScope::Objects || self.filter.matches(&system.lc_name))
+            && shortage
+            && industry
+            && self
+                .region
+                .as_deref()
+                .is_none_or(|region| system.region() == Some(region))
+            && self
+                .mission_faction
+                .as_deref()
+                .is_none_or(|faction| system.mission_factions().contains(&faction))
+            && self.resources.matches(system)
+            && self.stellar_hazard.matches(system)
+            && self
+                .min_danger
+                .is_none_or(|danger| system.danger().is_some_and(|d| d >= danger))
+            && self.min_colony_size.is_none_or(|size| {
+                system
+                    .objects
+                    .iter()
+                    .any(|o| o.market.is_some() && o.size().is_some_and(|s| s >= size))
+            })
+            && self.min_market_size.is_none_or(|size| {
+                system
+                    .objects
+                    .iter()
+                    .any(|o| o.market_size().is_some_and(|s| s >= size))
+            })
+            && self.atmosphere.is_none_or(|atmosphere| {
+                system
+                    .objects
+                    .iter()
+                    .any(|o| o.atmosphere == Some(atmosphere))
+            })
+            && self
+                .multiplicity
+                .is_none_or(|m| system.multiplicity() == Some(m))
+            && self.temperature.is_none_or(|temperature| {
+                system
+                    .objects
+                    .iter()
+                    .any(|o| o.temperature == Some(temperature))
+            })
+    }
+
+    /// The index of the next system with a mission before or after the selected one,
+    /// wrapping around at either end.
+    fn next_mission_system(&self, forward: bool) -> usize {
+        let len = self.systems.len();
+        (1..len)
+            .map(|offset| {
+                if forward {
+                    (self.selected + offset) % len
+                } else {
+                    (self.selected + len - offset) % len
+                }
+            })
+            .find(|&index| self.systems[index].mission && self.system_matches(&self.systems[index]))
+            .unwrap_or(self.selected)
+    }
+}
+
+impl eframe::App for ScanSectorUi {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, "settings", &self.settings);
+        eframe::set_value(storage, "favorites", &self.favorites);
+        let mut saves = std::mem::take(&mut self.saves);
+        if let Some(path) = &self.save {
+            saves.insert(path_key(path), self.save_state());
+        }
+        eframe::set_value(storage, "saves", &saves);
+        self.saves = saves;
+        eframe::set_value(storage, "recent", &self.recent);
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        egui::gui_zoom::zoom_with_keyboard_shortcuts(ctx, frame.info().native_pixels_per_point);
+
+        let dropped = ctx.input(|i| i.raw.dropped_files.iter().find_map(|f| f.path.clone()));
+        if let Some(path) = dropped {
+            self.load(path);
+        } else if let Some(path) = self.changed_save(ctx) {
+            if self.load(path) {
+                self.auto_export();
+            }
+        }
+        self.clamp_selection();
+        self.timeline.show(ctx);
+        egui::Window::new("Settings")
+            .open(&mut self.show_settings)
+            .show(ctx, |ui| {
+                self.settings.edit(ui);
+                ui.separator();
+                if ui.button("Reset to Defaults").clicked() {
+                    self.confirm_reset = true;
+                }
+            });
+        egui::Window::new("Help")
+            .open(&mut self.show_help)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| render_help(ui, &self.settings.plot));
+            });
+        if self.confirm_reset {
+            egui::Window::new("Reset Settings?")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label("Every setting will go back to its default.");
+                    ui.label("Favourites, notes and recent saves are kept.");
+                    ui.horizontal(|ui| {
+                        if ui.button("Reset").clicked() {
+                            self.settings = Settings::default();
+                            self.confirm_reset = false;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.confirm_reset = false;
+                        }
+                    });
+                });
+        }
+
+        egui::TopBottomPanel::top("footer").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.menu_button("View", |ui| {
+                    egui::gui_zoom::zoom_menu_buttons(ui, frame.info().native_pixels_per_point);
+
+                    egui::widgets::global_dark_light_mode_buttons(ui);
+
+                    ui.separator();
+                    ui.checkbox(&mut self.visibility.planets, "Show Planets");
+                    ui.checkbox(&mut self.visibility.stations, "Show Stations");
+                    ui.checkbox(&mut self.visibility.jump_points, "Show Jump Points");
+                    ui.checkbox(&mut self.visibility.entities, "Show Other Entities");
+                    ui.checkbox(&mut self.visibility.undiscovered, "Show Undiscovered");
+                    ui.checkbox(&mut self.table.show, "Show Object Table");
+                    ui.checkbox(
+                        &mut self.settings.plot.highlight_anomalies,
+                        "Highlight Anomalies",
+                    )
+                    .on_hover_text(
+                        "Mark unusually crowded systems and those with unique structures",
+                    );
+                    ui.checkbox(&mut self.settings.plot.mission_types, "Mission Types")
+                        .on_hover_text("Mark bounty, survey and delivery targets differently");
+                    ui.checkbox(&mut self.settings.plot.star_relative, "Centre on Star")
+                        .on_hover_text(
+                            "Measure positions from the primary star rather than the system's \
+                             origin",
+                        );
+                    ui.checkbox(&mut self.settings.plot.show_trails, "Trails")
+                        .on_hover_text(
+                            "Follow objects through the saves added to the campaign timeline",
+                        );
+                    ui.checkbox(&mut self.settings.plot.table_only, "Table Instead of Plot")
+                        .on_hover_text("List objects with their positions, if the plot misbehaves");
+                    ui.checkbox(
+                        &mut self.settings.plot.highlight_changes,
+                        "Highlight Changes",
+                    )
+                    .on_hover_text(
+                        "Briefly mark objects that appeared, disappeared or changed when the \
+                             save is reloaded",
+                    );
+
+                    ui.separator();
+                    if ui.button("Settings").clicked() {
+                        self.show_settings = true;
+                        ui.close_menu();
+                    }
+                });
+
+                if self
+                    .pick_file
+                    .as_ref()
+                    .map(|t| t.is_finished())
+                    .unwrap_or(false)
+                {
+                    let jh = self.pick_file.take().unwrap();
+                    if let Some(path) = jh.join().unwrap() {
+                        self.load(path);
+                    }
+                }
+
+                ui.toggle_value(&mut self.timeline.open, "Timeline")
+                    .on_hover_text("Chart progress across several saves of a campaign");
+                ui.toggle_value(&mut self.show_help, "?")
+                    .on_hover_text("What the markers, colours and filters mean");
+
+                let mut open = None;
+                ui.menu_button("Recent", |ui| {
+                    if self.recent.is_empty() {
+                        ui.label("No recent saves");
+                    }
+                    for path in &self.recent {
+                        if ui.button(path.to_string_lossy()).clicked() {
+                            open = Some(path.clone());
+                            ui.close_menu();
+                        }
+                    }
+
+                    ui.separator();
+                    if ui.button("Clear Recent").clicked() {
+                        self.recent.clear();
+                        ui.close_menu();
+                    }
+                });
+                if let Some(path) = open {
+                    self.load(path);
+                }
+
+                ui.add_enabled_ui(self.pick_file.is_none(), |ui| {
+                    if ui.button("Pick Save").clicked() {
+                        let ctx = ctx.clone();
+                        self.pick_file = Some(std::thread::spawn(move || {
+                            let path = rfd::FileDialog::new()
+                                .add_filter("Saves", &["xml", "gz"])
+                                .pick_file();
+                            ctx.request_repaint();
+                            path
+                        }));
+                    }
+                });
+
+                if self.pick_folder.as_ref().is_some_and(|t| t.is_finished()) {
+                    match self.pick_folder.take().unwrap().join().unwrap() {
+                        Some(Ok(campaigns)) if campaigns.is_empty() => {
+                            self.message = Some("No campaigns found in that folder".to_string());
+                        }
+                        Some(Ok(campaigns)) => {
+                            self.campaigns = campaigns;
+                            let first = self.campaigns[0].path.clone();
+                            self.load(first);
+                        }
+                        Some(Err(e)) => self.message = Some(format!("Failed to read folder: {e}")),
+                        None => {}
+                    }
+                }
+
+                ui.add_enabled_ui(self.pick_folder.is_none(), |ui| {
+                    if ui
+                        .button("Pick Saves Folder")
+                        .on_hover_text("Switch between every campaign saved in a folder")
+                        .clicked()
+                    {
+                        let ctx = ctx.clone();
+                        self.pick_folder = Some(std::thread::spawn(move || {
+                            let campaigns = rfd::FileDialog::new()
+                                .pick_folder()
+                                .map(|folder| find_campaigns(&folder));
+                            ctx.request_repaint();
+                            campaigns
+                        }));
+                    }
+                });
+
+                if !self.campaigns.is_empty() {
+                    let current = self
+                        .campaigns
+                        .iter()
+                        .find(|c| self.save.as_deref().is_some_and(|s| same_path(s, &c.path)));
+                    let mut picked = None;
+                    ComboBox::from_id_source("_campaign_select")
+                        .selected_text(current.map_or("Campaign".to_string(), Campaign::name))
+                        .show_ui(ui, |ui| {
+                            for campaign in &self.campaigns {
+                                let selected = current.is_some_and(|c| c.path == campaign.path);
+                                if ui.selectable_label(selected, campaign.name()).clicked() {
+                                    picked = Some(campaign.path.clone());
+                                }
+                            }
+                        });
+                    if let Some(path) = picked {
+                        self.load(path);
+                    }
+                }
+
+                if self
+                    .export_file
+                    .as_ref()
+                    .map(|(_, t)| t.is_finished())
+                    .unwrap_or(false)
+                {
+                    let (scope, jh) = self.export_file.take().unwrap();
+                    if let Some(path) = jh.join().unwrap() {
+                        self.export(scope, &path);
+                    }
+                }
+
+                ui.add_enabled_ui(
+                    !self.systems.is_empty() && self.export_file.is_none(),
+                    |ui| {
+                        let mut scope = None;
+                        let mut copied = None;
+                        ui.menu_button("Export", |ui| {
+                            let name = &self.systems[self.selected].name;
+                            if ui.button("Sector JSON").clicked() {
+                                scope = Some((ExportScope::All, "systems.json".to_string()));
+                            }
+                            if ui.button("System JSON").clicked() {
+                                scope = Some((ExportScope::Current, format!("{name}.json")));
+                            }
+                            if ui.button("Report").clicked() {
+                                scope = Some((ExportScope::Report, "report.md".to_string()));
+                            }
+                            if ui
+                                .button("Colony Candidates")
+                                .on_hover_text("A Markdown table of the best unclaimed planets")
+                                .clicked()
+                            {
+                                scope = Some((ExportScope::Candidates, "colonies.md".to_string()));
+                            }
+
+                            ui.separator();
+                            ui.add_enabled_ui(!self.marked.is_empty(), |ui| {
+                                let count = self.marked.len();
+                                if ui
+                                    .button(format!("Marked Systems JSON ({count})"))
+                                    .on_hover_text("Ctrl-click systems in the list to mark them")
+                                    .clicked()
+                                {
+                                    scope = Some((ExportScope::Marked, "marked.json".to_string()));
+                                }
+                                if ui.button(format!("Marked Systems CSV ({count})")).clicked() {
+                                    scope =
+                                        Some((ExportScope::MarkedCsv, "marked.csv".to_string()));
+                                }
+                                if ui
+                                    .button(format!("Marked Systems Card Sheet ({count})"))
+                                    .on_hover_text(
+                                        "A PNG with a small map and the key stats of each system",
+                                    )
+                                    .clicked()
+                                {
+                                    scope =
+                                        Some((ExportScope::MarkedSheet, "marked.png".to_string()));
+                                }
+                                if ui.button("Clear Marks").clicked() {
+                                    self.marked.clear();
+                                }
+                            });
+
+                            ui.separator();
+                            let count = self
+                                .systems
+                                .iter()
+                                .filter(|s| self.system_matches(s))
+                                .count();
+                            ui.add_enabled_ui(count > 0, |ui| {
+                                let hover = "The systems left in the list by the current filters";
+                                if ui
+                                    .button(format!("Filtered Systems JSON ({count})"))
+                                    .on_hover_text(hover)
+                                    .clicked()
+                                {
+                                    scope =
+                                        Some((ExportScope::Filtered, "filtered.json".to_string()));
+                                }
+                                if ui
+                                    .button(format!("Filtered Systems CSV ({count})"))
+                                    .on_hover_text(hover)
+                                    .clicked()
+                                {
+                                    scope = Some((
+                                        ExportScope::FilteredCsv,
+                                        "filtered.csv".to_string(),
+                                    ));
+                                }
+                                if ui
+                                    .button(format!("Copy Filtered JSON ({count})"))
+                                    .on_hover_text(
+                                        "Copy the filtered systems' JSON to paste elsewhere",
+                                    )
+                                    .clicked()
+                                {
+                                    let json = export::to_json(&self.filtered_systems());
+                                    ui.output_mut(|o| o.copied_text = json);
+                                    copied = Some(count);
+                                    ui.close_menu();
+                                }
+                            });
+
+                            ui.separator();
+                            let gates = self.systems.iter().any(|s| s.has_gate());
+                            ui.add_enabled_ui(gates, |ui| {
+                                if ui
+                                    .button("Gate Network")
+                                    .on_hover_text("A Graphviz DOT graph of the systems with gates")
+                                    .clicked()
+                                {
+                                    scope =
+                                        Some((ExportScope::GateNetwork, "gates.dot".to_string()));
+                                }
+                                ui.checkbox(&mut self.weight_gates, "With Distances");
+                            });
+
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                let (width, height) = &mut self.overlay_size;
+                                ui.label("Image");
+                                ui.add(egui::DragValue::new(width).clamp_range(1..=16384));
+                                ui.label("×");
+                                ui.add(egui::DragValue::new(height).clamp_range(1..=16384));
+                            });
+                            if ui
+                                .button("System Overlay")
+                                .on_hover_text(
+                                    "Object positions in pixels of an image of the system map",
+                                )
+                                .clicked()
+                            {
+                                let file_name = format!("{name}-overlay.json");
+                                scope = Some((ExportScope::Overlay, file_name));
+                            }
+
+                            if scope.is_some() {
+                                ui.close_menu();
+                            }
+                        });
+                        if let Some(count) = copied {
+                            self.status = Some(format!("Copied {count} systems as JSON"));
+                        }
+
+                        if let Some((scope, file_name)) = scope {
+                            let ctx = ctx.clone();
+                            let (filter, extension) = match scope {
+                                ExportScope::Report | ExportScope::Candidates => ("Markdown", "md"),
+                                ExportScope::MarkedCsv | ExportScope::FilteredCsv => ("CSV", "csv"),
+                                ExportScope::GateNetwork => ("Graphviz", "dot"),
+                                ExportScope::MarkedSheet => ("PNG image", "png"),
+                                _ => ("JSON", "json"),
+                            };
+                            let dialog = std::thread::spawn(move || {
+                                let path = rfd::FileDialog::new()
+                                    .add_filter(filter, &[extension])
+                                    .set_file_name(&file_name)
+                                    .save_file();
+                                ctx.request_repaint();
+                                path
+                            });
+                            self.export_file = Some((scope, dialog));
+                        }
+                    },
+                );
+
+                if let Some(path) = &self.save {
+                    ui.heading(path.to_string_lossy());
+                }
+
+                if let (surveyed, planets @ 1..) = self.survey_progress {
+                    ui.separator();
+                    ui.add(
+                        egui::ProgressBar::new(surveyed as f32 / planets as f32)
+                            .desired_width(200.0)
+                            .text(format!("{surveyed} of {planets} planets surveyed")),
+                    );
+                }
+
+                if let Some(seed) = &self.descriptor.seed {
+                    ui.separator();
+                    ui.label(format!("Seed: {seed}"));
+                    if ui
+                        .small_button("📋")
+                        .on_hover_text("Copy the seed")
+                        .clicked()
+                    {
+                        ui.output_mut(|o| o.copied_text = seed.clone());
+                    }
+                }
+            });
+        });
+
+        if let Some(system) = self.systems.get(self.selected) {
+            egui::TopBottomPanel::bottom("selection").show(ctx, |ui| {
+                let object = self.selected_object.and_then(|i| system.objects.get(i));
+                ui.label(selection_summary(system, object, self.settings.names));
+            });
+        }
+
+        if let Some(status) = &self.status {
+            egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
+                ui.label(status);
+            });
+        }
+
+        if let Some(system) = self.systems.get(self.selected) {
+            let mut selected_object = self.selected_object;
+            egui::SidePanel::right("details").show(ctx, |ui| {
+                ui.heading("Objects");
+                let hidden = &mut self.plot_state.hidden;
+                let hidden_here = hidden.iter().filter(|(s, ..)| *s == system.name).count();
+                if hidden_here > 0
+                    && ui
+                        .small_button(format!("Show All ({hidden_here} hidden)"))
+                        .clicked()
+                {
+                    hidden.retain(|(s, ..)| *s != system.name);
+                }
+                let hidden_objects = objects_among(system, hidden);
+                let favorites = objects_among(system, &self.favorites);
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() / 2.0)
+                    .show(ui, |ui| {
+                        for (index, object) in system.objects() {
+                            if self.visibility.shows(object) {
+                                let label = if favorites.contains(&index) {
+                                    format!("★ {}", object.name)
+                                } else {
+                                    object.name.clone()
+                                };
+                                ui.horizontal(|ui| {
+                                    let mut shown = !hidden_objects.contains(&index);
+                                    let toggle = ui
+                                        .checkbox(&mut shown, "")
+                                        .on_hover_text("Show this object on the plot");
+                                    let key = || object_key(system, index);
+                                    if toggle.changed() && !hidden.remove(&key()) {
+                                        hidden.insert(key());
+                                    }
+                                    ui.selectable_value(&mut selected_object, Some(index), label);
+                                });
+                            }
+                        }
+                    });
+
+                ui.separator();
+                let mut note = self.notes.get(&system.name).cloned().unwrap_or_default();
+                let header = if note.is_empty() {
+                    "Notes"
+                } else {
+                    "Notes 📝"
+                };
+                ui.collapsing(header, |ui| {
+                    let edit = egui::TextEdit::multiline(&mut note)
+                        .hint_text("Notes on this system, kept between runs")
+                        .desired_rows(3);
+                    if ui.add(edit).changed() {
+                        if note.trim().is_empty() {
+                            self.notes.remove(&system.name);
+                        } else {
+                            self.notes.insert(system.name.clone(), note);
+                        }
+                    }
+                });
+
+                let mut pinned = self.pinned;
+                if let Some((s, o)) = pinned {
+                    let (pinned_system, object) = (&self.systems[s], &self.systems[s].objects[o]);
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(format!("📌 Pinned from {}", pinned_system.name));
+                        if ui.small_button("Unpin").clicked() {
+                            pinned = None;
+                        }
+                    });
+                    ui.push_id("pinned", |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_source("object_details")
+                            .max_height(ui.available_height() / 2.0)
+                            .show(ui, |ui| {
+                                let core = core_distance(&self.systems, pinned_system);
+                                let (units, names) = (self.settings.units, self.settings.names);
+                                render_details(ui, pinned_system, object, core, units, names);
+                            });
+                    });
+                }
+
+                let shown = selected_object.filter(|&o| pinned != Some((self.selected, o)));
+                if let Some((index, object)) = shown.and_then(|i| Some((i, system.objects.get(i)?)))
+                {
+                    ui.separator();
+                    if ui
+                        .small_button("📌 Pin")
+                        .on_hover_text("Keep these details shown while looking at other objects")
+                        .clicked()
+                    {
+                        pinned = Some((self.selected, index));
+                    }
+                    egui::ScrollArea::vertical()
+                        .id_source("object_details")
+                        .show(ui, |ui| {
+                            let core = core_distance(&self.systems, system);
+                            let settings = &self.settings;
+                            render_details(
+                                ui,
+                                system,
+                                object,
+                                core,
+                                settings.units,
+                                settings.names,
+                            );
+                        });
+                }
+                self.pinned = pinned;
+            });
+            self.selected_object = selected_object;
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(message) = &self.message {
+                ui.label(message.clone());
+            }
+
+            if let Some(warning) = &self.warning {
+                ui.colored_label(ui.visuals().warn_fg_color, warning);
+            }
+
+            if !self.systems.is_empty() {
+                let previous = self.selected;
+                let mut navigated = false;
+                let mut picked_object = None;
+
+                let (back, forward) = ui.input(|i| {
+                    (
+                        i.pointer.button_pressed(egui::PointerButton::Extra1),
+                        i.pointer.button_pressed(egui::PointerButton::Extra2),
+                    )
+                });
+
+                // Number keys switch views, unless they're being typed into a text box
+                if !ctx.wants_keyboard_input() {
+                    let pressed = |key| ui.input(|i| i.key_pressed(key));
+                    if pressed(egui::Key::Num1) {
+                        self.sector_view = false;
+                    }
+                    if pressed(egui::Key::Num2) {
+                        self.sector_view = true;
+                    }
+                    if pressed(egui::Key::Num3) {
+                        self.sector_view = false;
+                        self.table.show = !self.table.show;
+                    }
+                }
+
+                egui::TopBottomPanel::top("system_selection")
+                    .resizable(true)
+                    .show_inside(ui, |ui| {
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            ui.heading("Select a System");
+                            ui.horizontal(|ui| {
+                                ui.add_enabled_ui(!self.history.back.is_empty(), |ui| {
+                                    if ui.button("⬅").on_hover_text("Back").clicked() || back {
+                                        if let Some(index) = self.history.back(self.selected) {
+                                            self.selected = index;
+                                            navigated = true;
+                                        }
+                                    }
+                                });
+                                ui.add_enabled_ui(!self.history.forward.is_empty(), |ui| {
+                                    if ui.button("➡").on_hover_text("Forward").clicked() || forward
+                                    {
+                                        if let Some(index) = self.history.forward(self.selected) {
+                                            self.selected = index;
+                                            navigated = true;
+                                        }
+                                    }
+                                });
+
+                                ui.label("Filter");
+                                let filter = ui
+                                    .text_edit_singleline(&mut self.filter.text)
+                                    .on_hover_text("Press Escape to clear");
+                                let regex = ui
+                                    .toggle_value(&mut self.filter.use_regex, ".*")
+                                    .on_hover_text("Match as a regular expression");
+                                if filter.changed() || regex.changed() {
+                                    self.filter.update();
+                                    self.update_matches();
+                                }
+                                if let Some(error) = &self.filter.error {
+                                    ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                                        .on_hover_text(error);
+                                }
+
+                                // Escape takes focus away from the filter before we see it, so
+                                // check for that as well as a press with nothing focused.
+                                let escape = ui.input(|i| i.key_pressed(egui::Key::Escape));
+                                let unfocused = ui.memory(|m| m.focus().is_none());
+                                if escape && (filter.lost_focus() || unfocused) {
+                                    self.filter.clear();
+                                    self.update_matches();
+                                }
+
+                                let scope = self.search_scope;
+                                ComboBox::from_id_source("_search_scope")
+                                    .selected_text(self.search_scope.name())
+                                    .show_ui(ui, |ui| {
+                                        for scope in SearchScope::ALL {
+                                            ui.selectable_value(
+                                                &mut self.search_scope,
+                                                scope,
+                                                scope.name(),
+                                            );
+                                        }
+                                    });
+                                if self.search_scope != scope {
+                                    self.update_matches();
+                                }
+
+                                ui.add_enabled_ui(self.systems.iter().any(|s| s.mission), |ui| {
+                                    if ui
+                                        .button("⏴")
+                                        .on_hover_text("Previous mission system")
+                                        .clicked()
+                                    {
+                                        self.selected = self.next_mission_system(false);
+                                    }
+                                    if ui
+                                        .button("⏵")
+                                        .on_hover_text("Next mission system")
+                                        .clicked()
+                                    {
+                                        self.selected = self.next_mission_system(true);
+                                    }
+                                });
+
+                                ComboBox::from_id_source("_grouping")
+                                    .selected_text(self.grouping.name())
+                                    .show_ui(ui, |ui| {
+                                        for grouping in Grouping::ALL {
+                                            ui.selectable_value(
+                                                &mut self.grouping,
+                                                grouping,
+                                                grouping.name(),
+                                            );
+                                        }
+                                    });
+
+                                if self.grouping != Grouping::None {
+                                    if ui.button("⊞").on_hover_text("Expand every group").clicked()
+                                    {
+                                        let groups: Vec<String> = (0..self.systems.len())
+                                            .filter(|&i| self.system_matches(&self.systems[i]))
+                                            .map(|i| self.grouping.group(&self.systems[i]).into())
+                                            .collect();
+                                        self.open_groups.extend(groups);
+                                    }
+                                    if ui
+                                        .button("⊟")
+                                        .on_hover_text("Collapse every group")
+                                        .clicked()
+                                    {
+                                        self.open_groups.clear();
+                                    }
+                                }
+
+                                ComboBox::from_id_source("_star_system_select")
+                                    .width(ui.available_width())
+                                    .selected_text(shorten(
+                                        self.settings.names.system(&self.systems[self.selected]),
+                                    ))
+                                    .show_ui(ui, |ui| {
+                                        let matching: Vec<usize> = (0..self.systems.len())
+                                            .filter(|&i| self.system_matches(&self.systems[i]))
+                                            .collect();
+                                        let mut list = |ui: &mut Ui, indices: &[usize]| {
+                                            for &index in indices {
+                                                let name = &self.systems[index].name;
+                                                let shown = self
+                                                    .settings
+                                                    .names
+                                                    .system(&self.systems[index]);
+                                                let short = shorten(shown);
+                                                let shortened = matches!(short, Cow::Owned(_));
+                                                let mut label = short.into_owned();
+                                                let system = &self.systems[index];
+                                                if let Some(multiplicity) = system
+                                                    .multiplicity()
+                                                    .filter(|&m| m != Multiplicity::Single)
+                                                {
+                                                    label = format!(
+                                                        "{label} ({})",
+                                                        multiplicity.name().to_lowercase()
+                                                    );
+                                                }
+                                                if system.remnant_stations() > 0 {
+                                                    label = format!("☠ {label}");
+                                                }
+                                                let missions = system.missions().count();
+                                                if missions > 0 {
+                                                    label = format!(
+                                                        "{label} [{missions} mission{}]",
+                                                        if missions == 1 { "" } else { "s" }
+                                                    );
+                                                }
+                                                if system.has_free_port() {
+                                                    label = format!("{label} [free port]");
+                                                }
+                                                let free = system.free_stable_locations();
+                                                if free > 0 {
+                                                    label = format!("{label} [{free} free]");
+                                                }
+                                                if self.notes.contains_key(name) {
+                                                    label = format!("📝 {label}");
+                                                }
+                                                if self.marked.contains(&index) {
+                                                    label = format!("✔ {label}");
+                                                }
+                                                let anomalies = self
+                                                    .anomalies
+                                                    .get(index)
+                                                    .filter(|_| {
+                                                        self.settings.plot.highlight_anomalies
+                                                    })
+                                                    .filter(|a| !a.is_empty());
+                                                if anomalies.is_some() {
+                                                    label = format!("✦ {label}");
+                                                }
+                                                let mut response = ui.selectable_label(
+                                                    self.selected == index,
+                                                    label,
+                                                );
+                                                let mut hover: Vec<String> = anomalies
+                                                    .into_iter()
+                                                    .flatten()
+                                                    .map(|a| a.describe())
+                                                    .collect();
+                                                if shortened {
+                                                    hover.insert(0, shown.to_string());
+                                                }
+                                                if !hover.is_empty() {
+                                                    response =
+                                                        response.on_hover_text(hover.join("\n"));
+                                                }
+                                                if !response.clicked() {
+                                                    continue;
+                                                }
+                                                // Ctrl-click marks systems for export rather
+                                                // than switching to them
+                                                if ui.input(|i| i.modifiers.command) {
+                                                    if !self.marked.remove(&index) {
+                                                        self.marked.insert(index);
+                                                    }
+                                                } else {
+                                                    self.selected = index;
+                                                }
+                                            }
+                                        };
+
+                                        if self.grouping == Grouping::None {
+                                            list(ui, &matching);
+                                            return;
+                                        }
+
+                                        let mut groups: BTreeMap<&str, Vec<usize>> =
+                                            BTreeMap::new();
+                                        for &index in &matching {
+                                            let group = self.grouping.group(&self.systems[index]);
+                                            groups.entry(group).or_default().push(index);
+                                        }
+                                        for (group, indices) in groups {
+                                            let header = format!("{group} ({})", indices.len());
+                                            let open = self.open_groups.contains(group);
+                                            let response = egui::CollapsingHeader::new(header)
+                                                .id_source(group)
+                                                .open(Some(open))
+                                                .show(ui, |ui| list(ui, &indices));
+                                            if response.header_response.clicked() {
+                                                if open {
+                                                    self.open_groups.remove(group);
+                                                } else {
+                                                    self.open_groups.insert(group.to_string());
+                                                }
+                                            }
+                                        }
+                                    });
+                            });
+
+                            ui.horizontal_wrapped(|ui| {
+                                let marks_procgen = self.marks_procgen;
+                                for filter in ToggleFilter::ALL {
+                                    let enabled =
+                                        marks_procgen || filter != ToggleFilter::HandAuthored;
+                                    let on = self.toggle_filter(filter);
+                                    ui.add_enabled(enabled, egui::Checkbox::new(on, filter.name()))
+                                        .on_hover_text(filter.description())
+                                        .on_disabled_hover_text(
+                                            "This save doesn't mark which systems were generated",
+                                        );
+                                }
+                            });
+
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label("Quick Filters");
+                                for filter in QuickFilter::ALL {
+                                    let on = self.quick_filters.contains(&filter);
+                                    if ui
+                                        .selectable_label(on, filter.name())
+                                        .on_hover_text(filter.description())
+                                        .clicked()
+                                    {
+                                        if on {
+                                            self.quick_filters.remove(&filter);
+                                        } else {
+                                            self.quick_filters.insert(filter);
+                                        }
+                                    }
+                                }
+                            });
+
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label("Shortage");
+                                ComboBox::from_id_source("_shortage_select")
+                                    .selected_text(self.shortage.as_deref().unwrap_or("Any"))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.shortage, None, "Any");
+                                        for commodity in &self.shortages {
+                                            ui.selectable_value(
+                                                &mut self.shortage,
+                                                Some(commodity.clone()),
+                                                commodity,
+                                            );
+                                        }
+                                    });
+
+                                ui.label("Industry");
+                                ComboBox::from_id_source("_industry_select")
+                                    .selected_text(self.industry.as_deref().unwrap_or("Any"))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.industry, None, "Any");
+                                        for industry in &self.industries {
+                                            ui.selectable_value(
+                                                &mut self.industry,
+                                                Some(industry.clone()),
+                                                industry,
+                                            );
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(
+                                        "Systems with a colony that has built this industry or \
+                                         structure",
+                                    );
+
+                                ui.label("Mission Faction");
+                                ComboBox::from_id_source("_mission_faction_select")
+                                    .selected_text(
+                                        self.mission_faction.as_deref().map_or("Any", faction_name),
+                                    )
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.mission_faction, None, "Any");
+                                        for faction in &self.mission_factions {
+                                            ui.selectable_value(
+                                                &mut self.mission_faction,
+                                                Some(faction.clone()),
+                                                faction_name(faction),
+                                            );
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("Systems with missions offered by this faction");
+
+                                ui.label("Region");
+                                ComboBox::from_id_source("_region_select")
+                                    .selected_text(self.region.as_deref().unwrap_or("Any"))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.region, None, "Any");
+                                        for region in &self.regions {
+                                            ui.selectable_value(
+                                                &mut self.region,
+                                                Some(region.clone()),
+                                                region,
+                                            );
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(
+                                        "Systems in this constellation, or quarter of the sector \
+                                         where the save doesn't say",
+                                    );
+
+                                ui.label("Colony Size");
+                                ComboBox::from_id_source("_colony_size_select")
+                                    .selected_text(
+                                        self.min_colony_size.map_or("Any", |size| size.name()),
+                                    )
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.min_colony_size, None, "Any");
+                                        for size in Size::ALL {
+                                            ui.selectable_value(
+                                                &mut self.min_colony_size,
+                                                Some(size),
+                                                format!("{} or larger", size.name()),
+                                            );
+                                        }
+                                    });
+
+                                ui.label("Market Size");
+                                ComboBox::from_id_source("_market_size_select")
+                                    .selected_text(
+                                        self.min_market_size
+                                            .map_or("Any".to_string(), |s| format!("{s}+")),
+                                    )
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.min_market_size, None, "Any");
+                                        for size in MARKET_SIZES {
+                                            ui.selectable_value(
+                                                &mut self.min_market_size,
+                                                Some(size),
+                                                format!("{size} or larger"),
+                                            );
+                                        }
+                                    });
+
+                                ui.label("Atmosphere");
+                                ComboBox::from_id_source("_atmosphere_select")
+                                    .selected_text(self.atmosphere.map_or("Any", |a| a.name()))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.atmosphere, None, "Any");
+                                        for atmosphere in Atmosphere::ALL {
+                                            ui.selectable_value(
+                                                &mut self.atmosphere,
+                                                Some(atmosphere),
+                                                atmosphere.name(),
+                                            );
+                                        }
+                                    });
+
+                                ui.label("Temperature");
+                                ComboBox::from_id_source("_temperature_select")
+                                    .selected_text(self.temperature.map_or("Any", |t| t.name()))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.temperature, None, "Any");
+                                        for temperature in Temperature::ALL {
+                                            ui.selectable_value(
+                                                &mut self.temperature,
+                                                Some(temperature),
+                                                temperature.name(),
+                                            );
+                                        }
+                                    });
+
+                                ui.label("Stars");
+                                ComboBox::from_id_source("_multiplicity_select")
+                                    .selected_text(self.multiplicity.map_or("Any", |m| m.name()))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.multiplicity, None, "Any");
+                                        for multiplicity in Multiplicity::ALL {
+                                            ui.selectable_value(
+                                                &mut self.multiplicity,
+                                                Some(multiplicity),
+                                                multiplicity.name(),
+                                            );
+                                        }
+                                    });
+
+                                ui.label("Remnant Danger");
+                                ComboBox::from_id_source("_danger_select")
+                                    .selected_text(self.min_danger.map_or("Any", |d| d.name()))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut self.min_danger, None, "Any");
+                                        for danger in Danger::ALL {
+                                            ui.selectable_value(
+                                                &mut self.min_danger,
+                                                Some(danger),
+                                                format!("{} or higher", danger.name()),
+                                            );
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("Going by the system's warning beacon");
+
+                                ui.label("Black Holes & Pulsars");
+                                ComboBox::from_id_source("_stellar_hazard_select")
+                                    .selected_text(self.stellar_hazard.name())
+                                    .show_ui(ui, |ui| {
+                                        for filter in HazardFilter::ALL {
+                                            ui.selectable_value(
+                                                &mut self.stellar_hazard,
+                                                filter,
+                                                filter.name(),
+                                            );
+                                        }
+                                    });
+                            });
+
+                            ui.horizontal_wrapped(|ui| self.resources.edit(ui));
+
+                            ui.collapsing("Nearest Resource", |ui| {
+                                let nearest = &mut self.nearest;
+                                ui.horizontal(|ui| {
+                                    ComboBox::from_id_source("_nearest_resource")
+                                        .selected_text(nearest.resource.name())
+                                        .show_ui(ui, |ui| {
+                                            for resource in Resource::ALL {
+                                                ui.selectable_value(
+                                                    &mut nearest.resource,
+                                                    resource,
+                                                    resource.name(),
+                                                );
+                                            }
+                                        });
+
+                                    let resource = nearest.resource;
+                                    nearest.min_grade =
+                                        nearest.min_grade.clamp(1, resource.max_grade());
+                                    ComboBox::from_id_source("_nearest_grade")
+                                        .selected_text(format!(
+                                            "{} or better",
+                                            resource.grade_name(nearest.min_grade)
+                                        ))
+                                        .show_ui(ui, |ui| {
+                                            for grade in 1..=resource.max_grade() {
+                                                ui.selectable_value(
+                                                    &mut nearest.min_grade,
+                                                    grade,
+                                                    resource.grade_name(grade),
+                                                );
+                                            }
+                                        });
+                                });
+
+                                let Some(origin) = self.systems[self.selected].location else {
+                                    ui.label("The selected system's location is unknown");
+                                    return;
+                                };
+
+                                let mut hits: Vec<(usize, f64, u8)> = self
+                                    .systems
+                                    .iter()
+                                    .enumerate()
+                                    .filter(|&(index, _)| index != self.selected)
+                                    .filter_map(|(index, system)| {
+                                        let grade = system
+                                            .resource(nearest.resource)
+                                            .filter(|&grade| grade >= nearest.min_grade)?;
+                                        Some((index, system.location?.distance(&origin), grade))
+                                    })
+                                    .collect();
+                                hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+                                hits.truncate(10);
+
+                                if hits.is_empty() {
+                                    ui.label("No systems found");
+                                }
+
+                                for (index, distance, grade) in hits {
+                                    let label = format!(
+                                        "{}: {} away, {}",
+                                        self.systems[index].name,
+                                        self.settings.units.format(distance),
+                                        nearest.resource.grade_name(grade)
+                                    );
+                                    if ui.selectable_label(false, label).clicked() {
+                                        self.selected = index;
+                                    }
+                                }
+                            });
+
+                            ui.collapsing("Home-world Candidates", |ui| {
+                                if self.home_worlds.is_empty() {
+                                    ui.label("No habitable terran-like worlds found");
+                                }
+
+                                for &(system, object, hazard) in self.home_worlds.iter().take(10) {
+                                    let label = format!(
+                                        "{} in {}: {:.0}% hazard",
+                                        self.systems[system].objects[object].name,
+                                        self.systems[system].name,
+                                        hazard * 100.0
+                                    );
+                                    if ui.selectable_label(false, label).clicked() {
+                                        self.selected = system;
+                                        picked_object = Some((system, object));
+                                    }
+                                }
+                            });
+
+                            let header = format!("Unvisited Systems ({})", self.unvisited.len());
+                            ui.collapsing(header, |ui| {
+                                if self.unvisited.is_empty() {
+                                    ui.label("Every system has been visited");
+                                }
+
+                                egui::ScrollArea::vertical()
+                                    .id_source("_unvisited")
+                                    .max_height(200.0)
+                                    .show(ui, |ui| {
+                                        for &(index, distance) in &self.unvisited {
+                                            let name = &self.systems[index].name;
+                                            let label = match distance {
+                                                Some(distance) => format!(
+                                                    "{name}: {} away",
+                                                    self.settings.units.format(distance)
+                                                ),
+                                                None => name.clone(),
+                                            };
+                                            if ui.selectable_label(false, label).clicked() {
+                                                self.selected = index;
+                                            }
+                                        }
+                                    });
+                            })
+                            .header_response
+                            .on_hover_text(
+                                "Systems the player has never entered, nearest to them first",
+                            );
+
+                            if self.search_scope == SearchScope::Objects && !self.filter.is_empty()
+                            {
+                                let hits = &self.match_hits;
+                                let header = format!("Object Matches ({})", hits.len());
+                                ui.collapsing(header, |ui| {
+                                    let pages = hits.len().div_ceil(MATCHES_PER_PAGE).max(1);
+                                    self.match_page = self.match_page.min(pages - 1);
+                                    let start = self.match_page * MATCHES_PER_PAGE;
+                                    let page =
+                                        &hits[start..hits.len().min(start + MATCHES_PER_PAGE)];
+
+                                    if pages > 1 {
+                                        ui.horizontal(|ui| {
+                                            let back = self.match_page > 0;
+                                            if ui
+                                                .add_enabled(back, egui::Button::new("◀"))
+                                                .clicked()
+                                            {
+                                                self.match_page -= 1;
+                                            }
+                                            ui.label(format!(
+                                                "{}–{} of {}",
+                                                start + 1,
+                                                start + page.len(),
+                                                hits.len()
+                                            ));
+                                            let on = self.match_page + 1 < pages;
+                                            if ui.add_enabled(on, egui::Button::new("▶")).clicked()
+                                            {
+                                                self.match_page += 1;
+                                            }
+                                        });
+                                    }
+
+                                    // Only the rows scrolled into view are laid out.
+                                    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+                                    egui::ScrollArea::vertical()
+                                        .id_source("_object_matches")
+                                        .max_height(300.0)
+                                        .show_rows(ui, row_height, page.len(), |ui, rows| {
+                                            for &(system, object) in &page[rows] {
+                                                let label = format!(
+                                                    "{} in {}",
+                                                    self.systems[system].objects[object].name,
+                                                    self.systems[system].name
+                                                );
+                                                if ui.selectable_label(false, label).clicked() {
+                                                    self.selected = system;
+                                                    picked_object = Some((system, object));
+                                                }
+                                            }
+                                        });
+                                });
+                            }
+
+                            ui.collapsing("Unique Structures", |ui| {
+                                if self.uniques.is_empty() {
+                                    ui.label("No unique structures found");
+                                }
+
+                                for &(system, object, unique) in &self.uniques {
+                                    let label = format!(
+                                        "{} in {}",
+                                        unique.name(),
+                                        self.systems[system].name
+                                    );
+                                    if ui.selectable_label(false, label).clicked() {
+                                        self.selected = system;
+                                        picked_object = Some((system, object));
+                                    }
+                                }
+                            });
+
+                            let header = format!("Territory ({} factions)", self.territory.len());
+                            ui.collapsing(header, |ui| {
+                                if self.territory.is_empty() {
+                                    ui.label("No faction controls a system");
+                                }
+
+                                let systems = self.systems.len().max(1) as f32;
+                                egui::Grid::new("territory_grid").show(ui, |ui| {
+                                    for (faction, count) in &self.territory {
+                                        ui.label(faction_name(faction));
+                                        ui.add(
+                                            egui::ProgressBar::new(*count as f32 / systems)
+                                                .desired_width(120.0)
+                                                .text(match count {
+                                                    1 => "1 system".to_string(),
+                                                    n => format!("{n} systems"),
+                                                }),
+                                        );
+                                        ui.end_row();
+                                    }
+                                });
+                            });
+                        });
+                    });
+
+                if !self.system_matches(&self.systems[self.selected]) {
+                    if let Some(index) = self.systems.iter().position(|s| self.system_matches(s)) {
+                        self.selected = index;
+                    }
+                }
+                self.clamp_selection();
+
+                if self.selected != previous {
+                    self.selected_object = None;
+                    self.plot_state.reset |= self.settings.plot.auto_fit && !self.plot_state.locked;
+                    if !navigated {
+                        self.history.visit(previous);
+                    }
+                }
+                // Unless the filter has moved the selection off the object's system since
+                if let Some((_, object)) = picked_object.filter(|&(s, _)| s == self.selected) {
+                    self.selected_object = Some(object);
+                    self.plot_state.center_on_object = Some(object);
+                }
+
+                ui.horizontal(|ui| {
+                    let system = &self.systems[self.selected];
+                    if ui
+                        .add_enabled(!self.sector_view, egui::Link::new("Sector"))
+                        .on_hover_text("Show every system on a map of the sector")
+                        .clicked()
+                    {
+                        self.sector_view = true;
+                    }
+                    if self.sector_view {
+                        return;
+                    }
+                    ui.label("▸");
+                    let object = self.selected_object.and_then(|i| system.objects.get(i));
+                    if ui
+                        .add_enabled(object.is_some(), egui::Link::new(&system.name))
+                        .on_hover_text("Deselect the object")
+                        .clicked()
+                    {
+                        self.selected_object = None;
+                    }
+                    if let Some(object) = object {
+                        ui.label("▸");
+                        ui.label(&object.name);
+                    }
+
+                    ui.separator();
+                    ui.checkbox(&mut self.visibility.missions_only, "Mission Objects Only");
+                });
+
+                if self.sector_view {
+                    ui.checkbox(&mut self.settings.plot.trade_links, "Trade Links")
+                        .on_hover_text(
+                            "Link the player's colonies to the nearest markets of other factions",
+                        );
+                    ui.add_enabled(
+                        !self.storms.is_empty(),
+                        egui::Checkbox::new(&mut self.settings.plot.show_storms, "Storms"),
+                    )
+                    .on_hover_text("Shade where storms are brewing or raging in hyperspace")
+                    .on_disabled_hover_text("This save doesn't say where any storms are");
+                    let matching = |system: &System| self.system_matches(system);
+                    let overlays = SectorOverlays {
+                        anomalies: &self.anomalies,
+                        links: &self.trade_links,
+                        storms: &self.storms,
+                        storm_distances: &self.storm_distances,
+                    };
+                    let picked = render_sector(
+                        ui,
+                        &self.systems,
+                        self.selected,
+                        &self.settings.plot,
+                        overlays,
+                        matching,
+                    );
+                    if let Some(index) = picked {
+                        if index != self.selected {
+                            self.history.visit(self.selected);
+                            self.selected = index;
+                            self.selected_object = None;
+                        }
+                        self.sector_view = false;
+                    }
+                    return;
+                }
+
+                let system = &self.systems[self.selected];
+                if self.settings.plot.table_only {
+                    let visible = |object: &Object| self.visibility.shows(object);
+                    if let Some(index) = render_object_table(ui, system, &mut self.table, visible) {
+                        self.selected_object = Some(index);
+                    }
+                    return;
+                }
+
+                if self.table.show {
+                    egui::TopBottomPanel::bottom("object_table")
+                        .resizable(true)
+                        .show_inside(ui, |ui| {
+                            let visible = |object: &Object| self.visibility.shows(object);
+                            if let Some(index) =
+                                render_object_table(ui, system, &mut self.table, visible)
+                            {
+                                self.selected_object = Some(index);
+                            }
+                        });
+                }
+
+                self.plot_state.trails = if self.settings.plot.show_trails {
+                    trails(self.timeline.saves.iter().map(|s| &s.1), &system.name)
+                } else {
+                    BTreeMap::new()
+                };
+                let age = self
+                    .changed_at
+                    .map_or(f32::INFINITY, |t| t.elapsed().as_secs_f32());
+                self.plot_state.change_fade = 1.0 - age / CHANGE_SECONDS;
+                self.plot_state.changes = match self.changes.get(&system.name) {
+                    Some(changes)
+                        if self.settings.plot.highlight_changes && age < CHANGE_SECONDS =>
+                    {
+                        ui.ctx().request_repaint();
+                        changes.clone()
+                    }
+                    _ => Vec::new(),
+                };
+                self.plot_state.units = self.settings.units;
+                let action = render_system(
+                    ui,
+                    system,
+                    &self.settings.plot,
+                    &mut self.plot_state,
+                    &self.favorites,
+                    &self.visibility,
+                    self.settings.names,
+                );
+
+                match action {
+                    Some(PlotAction::SelectObject(index)) => {
+                        self.selected_object = Some(index);
+                    }
+                    Some(PlotAction::ToggleFavorite(index)) => {
+                        let key = object_key(system, index);
+                        if !self.favorites.remove(&key) {
+                            self.favorites.insert(key);
+                        }
+                    }
+                    Some(PlotAction::ToggleCategory(category)) => {
+                        self.visibility.toggle_category(category);
+                    }
+                    None => (),
+                }
+            } else if self.message.is_none() {
+                render_placeholder(ui);
+            }
+        });
+    }
+}
+
+/// What's drawn over the systems on the sector map, each where the options call for it.
+struct SectorOverlays<'a> {
+    /// What's odd about each system, as [`anomalies`] has it.
+    anomalies: &'a [Vec<Anomaly>],
+    links: &'a [(usize, usize)],
+    storms: &'a [StormCell],
+    /// How far each system is from the nearest of `storms`.
+    storm_distances: &'a [Option<f64>],
+}
+
+/// How close a system has to be to a hyperspace storm for the sector map to say so.
+const STORM_NEARBY: f64 = UNITS_PER_LIGHT_YEAR;
+
+/// Plot every system with a known location, sized by how many objects it has and shaded by
+/// the quality of its headline resource, returning the index of any system clicked on.
+/// Systems hidden by the filters are dimmed, and `overlays` drawn where they're to be.
+fn render_sector(
+    ui: &mut Ui,
+    systems: &[System],
+    selected: usize,
+    options: &PlotOptions,
+    overlays: SectorOverlays,
+    matching: impl Fn(&System) -> bool,
+) -> Option<usize> {
+    use eframe::egui::plot::{Line, Plot, Points, Polygon};
+
+    let palette = &options.palette;
+    let SectorOverlays {
+        anomalies,
+        links,
+        storms,
+        storm_distances,
+    } = overlays;
+    let links = if options.trade_links { links } else { &[] };
+    let storms = if options.show_storms { storms } else { &[] };
+
+    let located: Vec<(usize, &System, Position)> = systems
+        .iter()
+        .enumerate()
+        .filter_map(|(index, system)| Some((index, system, system.location?)))
+        .collect();
+    if located.is_empty() {
+        ui.label("No systems in this save have a known location");
+        return None;
+    }
+
+    let response = Plot::new("sector_display")
+        .data_aspect(1.0)
+        .show_axes([false, false])
+        .show(ui, |ui| {
+            for storm in storms {
+                let half = storm.size / 2.0;
+                let (x, y) = (storm.center.x, storm.center.y);
+                let corners = [[-half, -half], [half, -half], [half, half], [-half, half]];
+                let cell: Vec<[f64; 2]> = corners.iter().map(|[dx, dy]| [x + dx, y + dy]).collect();
+                ui.polygon(
+                    Polygon::new(cell)
+                        .color(palette.hostile)
+                        .fill_alpha(if storm.raging { 0.35 } else { 0.15 })
+                        .width(0.0),
+                );
+            }
+
+            for &(from, to) in links {
+                let (Some(a), Some(b)) = (systems[from].location, systems[to].location) else {
+                    continue;
+                };
+                ui.line(
+                    Line::new(vec![[a.x, a.y], [b.x, b.y]])
+                        .color(palette.friendly.gamma_multiply(0.6))
+                        .width(1.5),
+                );
+            }
+
+            for &(index, system, location) in &located {
+                let quality = system.headline_resource().map_or(0.0, |(resource, grade)| {
+                    f32::from(grade) / f32::from(resource.max_grade())
+                });
+                let mut color = palette.planet.gamma_multiply(0.3 + 0.7 * quality);
+                if !matching(system) {
+                    color = color.gamma_multiply(0.25);
+                }
+                let radius = 3.0 + (system.objects.len() as f32).sqrt() * 1.5;
+                let point = [location.x, location.y];
+                ui.points(Points::new(vec![point]).radius(radius).color(color));
+                if index == selected {
+                    ui.points(
+                        Points::new(vec![point])
+                            .radius(radius + 4.0)
+                            .filled(false)
+                            .color(palette.mission),
+                    );
+                }
+                let anomalous = anomalies.get(index).is_some_and(|a| !a.is_empty());
+                if options.highlight_anomalies && anomalous {
+                    ui.points(
+                        Points::new(vec![point])
+                            .radius(radius + 8.0)
+                            .filled(false)
+                            .color(palette.unique),
+                    );
+                }
+            }
+
+            let points = located
+                .iter()
+                .map(|&(index, _, location)| (index, location));
+            pick(ui, points, ui.pointer_coordinate()?)
+        });
+
+    let hovered = response.inner?;
+    let system = &systems[hovered];
+    let response = response.response.on_hover_ui_at_pointer(|ui| {
+        ui.strong(&system.name);
+        ui.label(format!("{} objects", system.objects.len()));
+        if let Some((resource, grade)) = system.headline_resource() {
+            ui.label(format!(
+                "{} {}",
+                resource.grade_name(grade),
+                resource.name()
+            ));
+        }
+        if let Some(faction) = system.controlling_faction() {
+            ui.label(format!("Controlled by {}", faction_name(faction)));
+        }
+        if system.mission {
+            ui.label("Mission objective");
+        }
+        match storm_distances.get(hovered).copied().flatten() {
+            Some(0.0) => {
+                ui.label("In a hyperspace storm");
+            }
+            Some(distance) if distance <= STORM_NEARBY => {
+                ui.label(format!(
+                    "{:.1} ly from a hyperspace storm",
+                    distance / UNITS_PER_LIGHT_YEAR
+                ));
+            }
+            _ => {}
+        }
+    });
+    response.clicked().then_some(hovered)
+}
+
+/// How many resources the status bar names for the selected system.
+const SUMMARY_RESOURCES: usize = 3;
+
+/// A line on the selected system, and object if there is one, for the status bar.
+fn selection_summary(system: &System, object: Option<&Object>, names: NameStyle) -> String {
+    let mut parts = vec![names.system(system).to_string()];
+    if let Some(object) = object {
+        parts[0] = format!("{} ▸ {}", parts[0], names.object(object));
+    }
+    parts.push(match system.objects.len() {
+        1 => "1 object".to_string(),
+        count => format!("{count} objects"),
+    });
+    parts.push(match system.controlling_faction() {
+        Some(faction) => format!("Controlled by {}", faction_name(faction)),
+        None => "Unclaimed".to_string(),
+    });
+    let resources: Vec<String> = system
+        .headline_resources()
+        .into_iter()
+        .take(SUMMARY_RESOURCES)
+        .map(|(resource, grade)| format!("{} {}", resource.grade_name(grade), resource.name()))
+        .collect();
+    if !resources.is_empty() {
+        parts.push(resources.join(", "));
+    }
+    parts.join(" · ")
+}
+
+/// How many of the nearest markets each player colony is linked to on the sector map.
+const TRADE_LINKS_PER_COLONY: usize = 3;
+
+/// A sortable, filterable table of the visible objects in `system`, returning the index of any
+/// object clicked on.
+fn render_object_table(
+    ui: &mut Ui,
+    system: &System,
+    table: &mut ObjectTable,
+    visible: impl Fn(&Object) -> bool,
+) -> Option<usize> {
+    ui.horizontal(|ui| {
+        ui.label("Filter");
+        ui.text_edit_singleline(&mut table.filter);
+    });
+
+    let filter = table.filter.to_lowercase();
+    let mut rows: Vec<(usize, &Object)> = system
+        .objects()
+        .filter(|(_, o)| visible(o))
+        .filter(|(_, o)| {
+            filter.is_empty()
+                || o.name.to_lowercase().contains(&filter)
+                || object_type(o).contains(&filter)
+                || conditions(o).contains(&filter)
+                || o.faction.as_deref().is_some_and(|f| {
+                    f.contains(&filter) || faction_name(f).to_lowercase().contains(&filter)
+                })
+        })
+        .collect();
+    rows.sort_by(|a, b| table.sort.compare(a.1, b.1));
+    if table.descending {
+        rows.reverse();
+    }
+
+    let mut clicked = None;
+    egui::ScrollArea::both().show(ui, |ui| {
+        egui::Grid::new("object_table_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                for column in Column::ALL {
+                    let arrow = match (table.sort == column, table.descending) {
+                        (true, false) => " ⏶",
+                        (true, true) => " ⏷",
+                        (false, _) => "",
+                    };
+                    if ui.button(format!("{}{arrow}", column.name())).clicked() {
+                        table.descending = table.sort == column && !table.descending;
+                        table.sort = column;
+                    }
+                }
+                ui.end_row();
+
+                for (index, object) in rows {
+                    if ui.selectable_label(false, &object.name).clicked() {
+                        clicked = Some(index);
+                    }
+                    ui.label(object_type(object));
+                    ui.label(format!("{:.0}, {:.0}", object.pos.x, object.pos.y));
+                    ui.label(conditions(object));
+                    ui.label(object.faction.as_deref().map_or("", faction_name));
+                    ui.label(
+                        object
+                            .market_size()
+                            .map_or(String::new(), |s| s.to_string()),
+                    );
+                    ui.label(if object.mission { "Yes" } else { "" });
+                    ui.end_row();
+                }
+            });
+    });
+
+    clicked
+}
+
+fn render_placeholder(ui: &mut Ui) {
+    ui.vertical_centered(|ui| {
+        ui.add_space(ui.available_height() / 5.0);
+        ui.heading("No save loaded");
+        ui.label("Use Pick Save, or drag a campaign.xml onto this window.");
+        ui.label("Saves live in the saves directory of your Starsector install.");
+        ui.add_space(20.0);
+
+        // A little illustration of what a loaded system looks like
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(240.0, 240.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let center = rect.center();
+        let faint = ui.visuals().weak_text_color();
+        let palette = Palette::default();
+        painter.circle_filled(center, 12.0, Color32::from_rgb(0xf5, 0xc8, 0x42));
+        for (radius, angle, color) in [
+            (45.0, 0.8, palette.planet),
+            (80.0, 2.9, palette.unique),
+            (110.0, 4.6, palette.entity),
+        ] {
+            painter.circle_stroke(center, radius, (1.0, faint));
+            let planet = center + radius * egui::Vec2::angled(angle);
+            painter.circle_filled(planet, 6.0, color);
+        }
+    });
+}
+
+/// The most characters of a name shown on the plot and in the system list, as some mods give
+/// objects names long enough to push everything else out of the way.
+const MAX_NAME_CHARS: usize = 40;
+
+/// `name` cut short with an ellipsis if it's longer than [`MAX_NAME_CHARS`].
+fn shorten(name: &str) -> Cow<'_, str> {
+    if name.chars().count() <= MAX_NAME_CHARS {
+        return Cow::Borrowed(name);
+    }
+    // Leave room for the ellipsis within the limit
+    let end = name
+        .char_indices()
+        .nth(MAX_NAME_CHARS - 1)
+        .map_or(name.len(), |(i, _)| i);
+    Cow::Owned(format!("{}…", &name[..end]))
+}
+
+/// When the file at `path` was last written, where the platform records it.
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// A position as the game writes vectors, in saves and for the console.
+fn vector_text(pos: Position) -> String {
+    format!("{}|{}", pos.x, pos.y)
+}
+
+fn render_details(
+    ui: &mut Ui,
+    system: &System,
+    object: &Object,
+    core_distance: Option<f64>,
+    units: Units,
+    names: NameStyle,
+) {
+    ui.heading(names.object(object));
+
+    egui::Grid::new("object_details_grid").show(ui, |ui| {
+        // Whichever name isn't the heading
+        let other = match names {
+            NameStyle::Friendly => object.internal_id.as_deref().map(|id| ("Internal Id", id)),
+            NameStyle::Internal => Some(("Name", object.name.as_str())),
+        };
+        if let Some((label, name)) = other.filter(|&(_, name)| name != names.object(object)) {
+            ui.label(label);
+            ui.label(name);
+            ui.end_row();
+        }
+
+        ui.label("Kind");
+        ui.label(match object.kind {
+            Kind::Planet => "Planet",
+            Kind::Entity => "Entity",
+        });
+        ui.end_row();
+
+        ui.label("Position");
+        ui.horizontal(|ui| {
+            ui.label(format!("{:.0}, {:.0}", object.pos.x, object.pos.y));
+            if ui
+                .small_button("📋")
+                .on_hover_text("Copy as x|y, as vectors are written in saves and the console")
+                .clicked()
+            {
+                ui.output_mut(|o| o.copied_text = vector_text(object.pos));
+            }
+        });
+        ui.end_row();
+
+        if let Some(planet_type) = &object.planet_type {
+            ui.label("Type");
+            ui.label(planet_type);
+            ui.end_row();
+        }
+
+        let index = system.objects.iter().position(|o| std::ptr::eq(o, object));
+        if let Some(kind) = index.and_then(|i| system.jump_point(i)) {
+            ui.label("Jump Point");
+            ui.label(kind.name());
+            ui.end_row();
+        }
+
+        if let Some(kind) = object.infrastructure() {
+            ui.label("Infrastructure");
+            let era = if object.domain_era() {
+                "Domain-era"
+            } else {
+                "Makeshift"
+            };
+            let state = if object.operational() {
+                "operational"
+            } else {
+                "inactive"
+            };
+            ui.label(format!("{era} {}, {state}", kind.name().to_lowercase()));
+            ui.end_row();
+        }
+
+        if let Some(state) = object.gate_state() {
+            ui.label("Gate");
+            ui.label(state.name());
+            ui.end_row();
+        }
+
+        if let Some(unique) = object.unique() {
+            ui.label("Unique");
+            ui.label(unique.name());
+            ui.end_row();
+        }
+
+        if let Some(atmosphere) = object.atmosphere {
+            ui.label("Atmosphere");
+            ui.label(atmosphere.name());
+            ui.end_row();
+        }
+
+        if let Some(temperature) = object.temperature {
+            ui.label("Temperature");
+            ui.label(temperature.name());
+            ui.end_row();
+        }
+
+        if object.tidally_locked {
+            ui.label("Rotation");
+            ui.label("Tidally locked");
+            ui.end_row();
+        }
+
+        if !object.rings.is_empty() {
+            ui.label("Rings");
+            ui.label(match object.rings.len() {
+                1 => "1 band".to_string(),
+                n => format!("{n} bands"),
+            });
+            ui.end_row();
+        }
+
+        if !object.salvage.is_empty() {
+            ui.label("Salvage");
+            ui.label(object.salvage.join(", "));
+            ui.end_row();
+        }
+
+        if let Some(market) = object
+            .market
+            .as_ref()
+            .filter(|m| m.resources().next().is_some())
+        {
+            let deposits: Vec<String> = market
+                .resources()
+                .map(|(resource, grade)| {
+                    format!("{} {}", resource.grade_name(grade), resource.name())
+                })
+                .collect();
+            ui.label(Supply::Sustained.name());
+            ui.label(deposits.join(", "));
+            ui.end_row();
+        }
+
+        let caches: Vec<&str> = object.one_time_resources().map(Resource::name).collect();
+        if !caches.is_empty() {
+            ui.label(Supply::OneTime.name());
+            ui.label(caches.join(", "));
+            ui.end_row();
+        }
+
+        if let (Some(size), Some(radius)) = (object.size(), object.radius) {
+            ui.label("Size");
+            ui.label(format!("{} (radius {radius:.0})", size.name()));
+            ui.end_row();
+        }
+
+        if let Some(parent) = object.parent.and_then(|i| system.objects.get(i)) {
+            ui.label("Orbits");
+            ui.label(&parent.name);
+            ui.end_row();
+
+            let orbit = object.orbit.unwrap_or_default();
+            if orbit.radius.is_some() || orbit.period.is_some() {
+                ui.label("Orbit");
+                ui.label(match (orbit.radius, orbit.period) {
+                    (Some(r), Some(p)) => format!("radius {r:.0}, {p:.0} days round"),
+                    (Some(r), None) => format!("radius {r:.0}, stationary"),
+                    (None, Some(p)) => format!("{p:.0} days round"),
+                    (None, None) => unreachable!(),
+                });
+                ui.end_row();
+            }
+
+            let index = system.objects.iter().position(|o| std::ptr::eq(o, object));
+            if let Some(angle) = index.and_then(|i| system.orbital_angle(i)) {
+                ui.label("Phase");
+                let phase = match orbit.angle_after(30.0) {
+                    Some(later) => format!("{angle:.0}° now, {later:.0}° in 30 days"),
+                    None => format!("{angle:.0}°"),
+                };
+                ui.label(phase);
+                ui.end_row();
+            }
+        }
+
+        if let Some(faction) = object.faction.as_deref().map(faction_name) {
+            ui.label("Faction");
+            ui.label(match object.standing() {
+                Some(Standing::Hostile) => format!("{faction} (hostile)"),
+                Some(Standing::Friendly) => format!("{faction} (friendly)"),
+                Some(Standing::Neutral) => format!("{faction} (neutral)"),
+                None => faction.to_string(),
+            });
+            ui.end_row();
+        }
+
+        for mission in &object.missions {
+            ui.label("Mission");
+            let mut details = vec![mission
+                .kind
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string())];
+            if let Some(faction) = &mission.faction {
+                details.push(format!("for {}", faction_name(faction)));
+            }
+            if let Some(reward) = mission.reward {
+                details.push(format!("{reward:.0} credits"));
+            }
+            ui.label(details.join(", "));
+            ui.end_row();
+        }
+
+        if let Some(survey) = object.survey() {
+            ui.label("Survey");
+            ui.label(survey.name());
+            ui.end_row();
+        }
+
+        if !object.discovered {
+            ui.label("Discovered");
+            ui.label("No");
+            ui.end_row();
+        }
+    });
+
+    if let Some(market) = &object.market {
+        ui.separator();
+        ui.strong("Market");
+        ui.label(format!("Estimated hazard: {:.0}%", market.hazard() * 100.0));
+        let breakdown = market.hazard_breakdown();
+        if !breakdown.is_empty() {
+            ui.collapsing("Hazard Breakdown", |ui| {
+                egui::Grid::new("hazard_breakdown_grid").show(ui, |ui| {
+                    for (source, modifier) in breakdown {
+                        let conditions: Vec<&str> = market
+                            .hazards()
+                            .filter(|h| h.1 == source)
+                            .map(|h| h.0)
+                            .collect();
+                        ui.label(source.name());
+                        ui.label(format!("{:+.0}%", modifier * 100.0));
+                        ui.label(conditions.join(", "));
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+        if let Some(size) = market.size {
+            ui.label(format!("Size: {size}"));
+        }
+        ui.label(format!(
+            "Port: {}",
+            market.port().map_or("None", |p| p.name())
+        ));
+        if market.free_port {
+            ui.label("Free port: illegal goods trade openly");
+        }
+        if !market.industries.is_empty() {
+            let industries: Vec<&str> =
+                market.industries.iter().map(|i| industry_name(i)).collect();
+            ui.label(format!("Industries: {}", industries.join(", ")));
+        }
+        if let Some(defense) = market.defense() {
+            let structures: Vec<&str> = market.defenses().map(|(name, _)| name).collect();
+            ui.label(if structures.is_empty() {
+                format!("Defences: {}", defense.name())
+            } else {
+                format!("Defences: {} ({})", defense.name(), structures.join(", "))
+            });
+        }
+
+        if let Some(distance) = core_distance {
+            ui.label(format!(
+                "{} from the core, roughly -{:.0}% accessibility",
+                units.format(distance),
+                estimated_accessibility_penalty(distance) * 100.0
+            ));
+        }
+
+        if !market.conditions.is_empty() {
+            ui.label(format!("Conditions: {}", market.conditions.join(", ")));
+        }
+
+        let mut deficits = market.deficits().peekable();
+        if deficits.peek().is_none() {
+            ui.label("No shortages");
+        } else {
+            egui::Grid::new("market_deficits_grid").show(ui, |ui| {
+                for commodity in deficits {
+                    ui.label(&commodity.id);
+                    ui.label(format!(
+                        "short {:.0} ({:.0} of {:.0})",
+                        commodity.deficit(),
+                        commodity.available,
+                        commodity.demand
+                    ));
+                    ui.end_row();
+                }
+            });
+        }
+    }
+
+    ui.separator();
+    ui.collapsing("Raw Data", |ui| {
+        ui.label("The object as written in the save, to share when something looks wrong.");
+        if !object.raw.attributes.is_empty() {
+            egui::Grid::new("object_raw_attributes").show(ui, |ui| {
+                for (name, value) in &object.raw.attributes {
+                    ui.monospace(name);
+                    ui.monospace(value);
+                    ui.end_row();
+                }
+            });
+        }
+        if let Some(j0) = object.raw.pretty_j0() {
+            if ui.small_button("📋 Copy JSON").clicked() {
+                ui.output_mut(|o| o.copied_text = j0.clone());
+            }
+            egui::ScrollArea::vertical()
+                .id_source("object_raw_j0")
+                .max_height(300.0)
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut j0.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+        }
+    });
+}
+
+/// Plot interaction state carried between frames.
+#[derive(Debug, Default)]
+struct PlotState {
+    /// The object the context menu was opened on.
+    context_object: Option<usize>,
+    /// Where to start sliding the view to on the next frame.
+    center_on: Option<Position>,
+    /// An object to centre the view on, as [`PlotState::center_on`], wherever it's drawn.
+    center_on_object: Option<usize>,
+    /// The view sliding to a new centre.
+    pan: Option<Pan>,
+    /// Whether to reframe the view around the system's objects on the next frame.
+    reset: bool,
+    /// Highlights objects whose names contain it, dimming the rest.
+    search: String,
+    /// Draw labels even in systems too dense for them to be legible.
+    dense_labels: bool,
+    /// A category to pick out, fading every other object rather than hiding it.
+    focus: Option<Category>,
+    /// Where the system's objects were across the timeline's saves, by
+    /// [`Object::trail_key`], as [`trails`] has them.
+    trails: BTreeMap<String, Vec<Position>>,
+    /// Objects unticked in the object list, left off the plot.
+    hidden: BTreeSet<ObjectKey>,
+    /// Keep the view where it's been panned and zoomed to, rather than reframing it on
+    /// switching systems or double-clicking.
+    locked: bool,
+    /// Objects that are different since the save was last loaded.
+    changes: Vec<ObjectChange>,
+    /// How strongly to draw the changes, from 1 just after the reload down to 0.
+    change_fade: f32,
+    /// An object to label every other with its distance from.
+    distances_from: Option<ObjectKey>,
+    /// What to give the distances in, as the settings have it.
+    units: Units,
+}
+
+/// An object, by the name of its system and its [`System::object_key`] within it, which finds
+/// the same object after the save's reloaded.
+type ObjectKey = (String, String, usize);
+
+fn object_key(system: &System, index: usize) -> ObjectKey {
+    let (key, nth) = system.object_key(index);
+    (system.name.clone(), key.to_string(), nth)
+}
+
+/// The indices of the objects in `system` that are among `keys`.
+fn objects_among(system: &System, keys: &BTreeSet<ObjectKey>) -> HashSet<usize> {
+    keys.iter()
+        .filter(|(s, ..)| *s == system.name)
+        .filter_map(|(_, key, nth)| system.find_object(key, *nth))
+        .collect()
+}
+
+/// How long changes stay highlighted after a reload.
+const CHANGE_SECONDS: f32 = 4.0;
+
+/// How long the view takes to slide to a new centre.
+const PAN_SECONDS: f64 = 0.3;
+
+/// A slide of the view between two centres, by the time in seconds it started.
 #[derive(Debug, Clone, Copy)]
-struct Position {
-    x: f64,
-    y: f64,
-}
-
-#[derive(Clone, Debug)]
-struct Object {
-    name: String,
-    planet: bool,
-    pos: Position,
-    mission: bool,
-}
-
-#[derive(Clone, Debug)]
-struct System {
-    name: String,
-    lc_name: String,
-    objects: Vec<Object>,
-    mission: bool,
-}
-
-fn load_save(path: &Path) -> std::io::Result<Vec<System>> {
-    let save = std::fs::read_to_string(path)?;
-    let doc = roxmltree::Document::parse(&save).unwrap();
-    let mut systems = vec![];
-
-    for sys in doc.descendants().filter(|n| n.tag_name().name() == "Sstm") {
-        let Some(name) = sys.attribute("bN") else { continue };
-        let mut system = System {
-            name: name.to_string(),
-            lc_name: name.to_lowercase(),
-            objects: vec![],
-            mission: false,
+struct Pan {
+    from: Position,
+    to: Position,
+    started: f64,
+}
+
+impl Pan {
+    /// Where the view should be centred at `time`, easing in and out, and whether it's there.
+    fn at(&self, time: f64) -> (Position, bool) {
+        let t = ((time - self.started) / PAN_SECONDS).clamp(0.0, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        let lerp = |a: f64, b: f64| a + (b - a) * eased;
+        let center = Position {
+            x: lerp(self.from.x, self.to.x),
+            y: lerp(self.from.y, self.to.y),
         };
+        (center, t >= 1.0)
+    }
+}
+
+/// Requests from the plot that affect the rest of the application.
+enum PlotAction {
+    SelectObject(usize),
+    ToggleFavorite(usize),
+    ToggleCategory(Category),
+}
+
+/// How near, in screen pixels, a click must land to something on a plot to pick it.  Measured
+/// on screen rather than in game units, so it's as easy to hit at any zoom.
+const PICK_RADIUS: f32 = 16.0;
+
+/// The index of the point nearest to `pointer`, if any are within [`PICK_RADIUS`].  Every
+/// click and hover on the plots goes through this, so they all agree on what's under the
+/// pointer.
+fn pick(
+    ui: &PlotUi,
+    points: impl IntoIterator<Item = (usize, Position)>,
+    pointer: PlotPoint,
+) -> Option<usize> {
+    let pointer = ui.screen_from_plot(pointer);
+    points
+        .into_iter()
+        .map(|(index, pos)| {
+            let pos = ui.screen_from_plot(PlotPoint::new(pos.x, pos.y));
+            (index, pos.distance(pointer))
+        })
+        .filter(|&(_, distance)| distance <= PICK_RADIUS)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+/// The index of the object nearest to `pointer`, if any is near enough to pick.
+fn object_at(ui: &PlotUi, objects: &[(usize, &Object)], pointer: PlotPoint) -> Option<usize> {
+    pick(
+        ui,
+        objects.iter().map(|&(index, o)| (index, o.pos)),
+        pointer,
+    )
+}
+
+/// Beyond this many visible objects, as in some modded sectors, labels are hidden unless asked
+/// for: they overlap into an unreadable smear and slow the plot to a crawl.
+const DENSE_SYSTEM_OBJECTS: usize = 150;
+
+/// The least padding around a system, in game units, so lone objects stay in view.
+const MIN_PADDING: f64 = 500.0;
+
+/// The least distance the view reaches either side of a system's centre, in game units, so a
+/// system with a single object, or everything at its centre, isn't zoomed right in on it.
+const MIN_VIEW_EXTENT: f64 = 1500.0;
+
+/// How strongly trails are drawn next to the objects they follow.
+const TRAIL_FADE: f32 = 0.35;
+
+/// Half-widths reaching at least `x` and `y` either side of the centre, widened along one
+/// axis so they fill a view `aspect` times as wide as it is tall with a 1:1 scale.
+fn fit_aspect(x: f64, y: f64, aspect: f64) -> (f64, f64) {
+    if x < y * aspect {
+        (y * aspect, y)
+    } else {
+        (x, x / aspect)
+    }
+}
 
-        for planet in sys.descendants().filter(|n| n.tag_name().name() == "Plnt") {
-            let Some(mut planet) = extract_object(&planet) else { continue };
-            planet.planet = true;
-            system.objects.push(planet);
+/// The shape an object is drawn with on the system plot.
+fn marker_shape(system: &System, index: usize, options: &PlotOptions) -> MarkerShape {
+    let object = &system.objects[index];
+    if let Some(kind) = object.mission_type().filter(|_| options.mission_types) {
+        mission_marker(kind)
+    } else if object.star_class().and_then(StarClass::hazard).is_some() {
+        MarkerShape::Square
+    } else if object.kind == Kind::Planet {
+        MarkerShape::Circle
+    } else if let Some(kind) = system.jump_point(index) {
+        match kind {
+            JumpPointKind::Inner => MarkerShape::Up,
+            JumpPointKind::Fringe => MarkerShape::Down,
         }
+    } else if object.is_gate() {
+        MarkerShape::Circle
+    } else if object.unique().is_some() {
+        MarkerShape::Diamond
+    } else if object.mission {
+        MarkerShape::Asterisk
+    } else {
+        MarkerShape::Cross
+    }
+}
 
-        for ent in sys.descendants().filter(|n| n.tag_name().name() == "CCEnt") {
-            let Some(ent) = extract_object(&ent) else { continue };
-            system.objects.push(ent);
-        }
-        system.mission = system.objects.iter().any(|ent| ent.mission);
-        systems.push(system);
+fn mission_marker(kind: MissionType) -> MarkerShape {
+    match kind {
+        MissionType::Bounty => MarkerShape::Plus,
+        MissionType::Survey => MarkerShape::Asterisk,
+        MissionType::Delivery => MarkerShape::Right,
+        MissionType::Other => MarkerShape::Square,
     }
+}
 
-    systems.sort_unstable_by_key(|s| s.name.clone());
+/// Draw `shape` as it's drawn on the plot, for a legend; outlined if it isn't `filled`.
+fn marker_swatch(ui: &mut Ui, shape: MarkerShape, color: Color32, filled: bool) {
+    use egui::{pos2, vec2, Shape, Stroke};
 
-    Ok(systems)
+    let (rect, _) = ui.allocate_exact_size(vec2(16.0, 16.0), egui::Sense::hover());
+    let (c, r) = (rect.center(), 6.0);
+    let stroke = Stroke::new(2.0, color);
+    let painter = ui.painter();
+    let polygon = |points: &[(f32, f32)]| {
+        let points = points
+            .iter()
+            .map(|&(x, y)| pos2(c.x + x, c.y + y))
+            .collect();
+        if filled {
+            Shape::convex_polygon(points, color, Stroke::NONE)
+        } else {
+            Shape::closed_line(points, stroke)
+        }
+    };
+    match shape {
+        MarkerShape::Circle if filled => {
+            painter.circle_filled(c, r, color);
+        }
+        MarkerShape::Circle => {
+            painter.circle_stroke(c, r, stroke);
+        }
+        MarkerShape::Diamond => {
+            painter.add(polygon(&[(0.0, -r), (r, 0.0), (0.0, r), (-r, 0.0)]));
+        }
+        MarkerShape::Square => {
+            painter.add(polygon(&[(-r, -r), (r, -r), (r, r), (-r, r)]));
+        }
+        MarkerShape::Right => {
+            painter.add(polygon(&[(-r, -r), (r, 0.0), (-r, r)]));
+        }
+        MarkerShape::Plus => {
+            painter.line_segment([c - vec2(r, 0.0), c + vec2(r, 0.0)], stroke);
+            painter.line_segment([c - vec2(0.0, r), c + vec2(0.0, r)], stroke);
+        }
+        // Screen coordinates run downwards, unlike the plot's
+        MarkerShape::Up => {
+            painter.add(polygon(&[(0.0, -r), (r, r), (-r, r)]));
+        }
+        MarkerShape::Down => {
+            painter.add(polygon(&[(-r, -r), (r, -r), (0.0, r)]));
+        }
+        MarkerShape::Asterisk => {
+            for angle in [0.0, 60.0, 120.0_f32] {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let offset = vec2(r * sin, r * cos);
+                painter.line_segment([c - offset, c + offset], stroke);
+            }
+        }
+        _ => {
+            painter.line_segment([c - vec2(r, r), c + vec2(r, r)], stroke);
+            painter.line_segment([c - vec2(r, -r), c + vec2(r, -r)], stroke);
+        }
+    }
 }
 
-fn parse_vector(v: &str) -> Option<Position> {
-    let mut split = v.split(|b| b == '|');
-    let x = split.next()?.parse().ok()?;
-    let y = split.next()?.parse().ok()?;
-    Some(Position { x, y })
-}
+/// What the markers, colours and filters mean, and the shortcuts there are.
+fn render_help(ui: &mut Ui, options: &PlotOptions) {
+    let palette = &options.palette;
 
-fn extract_object(node: &Node) -> Option<Object> {
-    let loc = node.descendants().find(|n| n.tag_name().name() == "loc")?;
-    let loc = parse_vector(loc.text()?)?;
+    ui.strong("Markers");
+    egui::Grid::new("help_markers").show(ui, |ui| {
+        let mut markers = vec![
+            (
+                MarkerShape::Circle,
+                palette.planet,
+                "Planet or star".to_string(),
+            ),
+            (
+                MarkerShape::Square,
+                palette.planet,
+                "Black hole or pulsar".to_string(),
+            ),
+            (
+                MarkerShape::Up,
+                palette.entity,
+                "Inner jump point".to_string(),
+            ),
+            (
+                MarkerShape::Down,
+                palette.entity,
+                "Fringe jump point".to_string(),
+            ),
+            (
+                MarkerShape::Diamond,
+                palette.unique,
+                "Unique structure".to_string(),
+            ),
+        ];
+        if options.mission_types {
+            markers.extend(MissionType::ALL.into_iter().map(|kind| {
+                let meaning = format!("{} mission target", kind.name());
+                (mission_marker(kind), palette.mission, meaning)
+            }));
+        } else {
+            markers.push((
+                MarkerShape::Asterisk,
+                palette.mission,
+                "Mission target".to_string(),
+            ));
+        }
+        markers.push((
+            MarkerShape::Cross,
+            palette.entity,
+            "Station or other entity".to_string(),
+        ));
+        for (shape, color, meaning) in markers {
+            marker_swatch(ui, shape, color, true);
+            ui.label(meaning);
+            ui.end_row();
+        }
+        for state in [GateState::Active, GateState::Inactive] {
+            marker_swatch(
+                ui,
+                MarkerShape::Circle,
+                palette.entity,
+                state == GateState::Active,
+            );
+            ui.label(format!("Gate: {}", state.name().to_lowercase()));
+            ui.end_row();
+        }
+    });
+    ui.label("Objects matching the plot's search are drawn larger, and the rest faded.");
 
-    let mission = node.descendants().any(|n| n.tag_name().name() == "MReq");
+    ui.separator();
+    ui.strong("Colours");
+    egui::Grid::new("help_colours").show(ui, |ui| {
+        let mut colours = vec![
+            (palette.planet, "Planet"),
+            (palette.entity, "Entity"),
+            (palette.mission, "Mission target"),
+            (palette.unique, "Unique structure"),
+        ];
+        if options.color_by_standing {
+            colours.extend([
+                (palette.hostile, "Owned by a hostile faction"),
+                (
+                    palette.friendly,
+                    "Owned by a friendly faction or the player",
+                ),
+                (palette.neutral, "Owned by a neutral faction"),
+            ]);
+        }
+        colours.extend([
+            (terrain_color(TerrainKind::Nebula), "Nebula"),
+            (terrain_color(TerrainKind::AsteroidBelt), "Asteroid belt"),
+            (terrain_color(TerrainKind::Ring), "Ring"),
+        ]);
+        for (color, meaning) in colours {
+            marker_swatch(ui, MarkerShape::Circle, color, true);
+            ui.label(meaning);
+            ui.end_row();
+        }
+    });
 
-    let what = node.descendants().find(|n| n.tag_name().name() == "j0")?;
-    let what = json::parse(what.text()?).ok()?;
+    ui.separator();
+    ui.strong("Filters");
+    ui.label("Every filter applies at once: a system is listed only if it passes them all.");
+    egui::Grid::new("help_filters").show(ui, |ui| {
+        let mut filters: Vec<(String, &str)> = vec![
+            (
+                "Filter Systems".to_string(),
+                "Lists systems whose names contain the filter text",
+            ),
+            (
+                "Search All Objects".to_string(),
+                "Lists objects across the sector with matching names",
+            ),
+            (
+                ".*".to_string(),
+                "Matches the filter as a regular expression instead",
+            ),
+        ];
+        let toggles = ToggleFilter::ALL.into_iter();
+        filters.extend(toggles.map(|f| (f.name().to_string(), f.description())));
+        let quick = QuickFilter::ALL.into_iter();
+        filters.extend(quick.map(|f| (format!("Quick: {}", f.name()), f.description())));
+        let combines = Combine::ALL.into_iter();
+        filters.extend(combines.map(|c| (format!("Resources: {}", c.name()), c.description())));
+        let supplies = SupplyFilter::ALL.into_iter();
+        filters.extend(supplies.map(|s| (format!("Resources: {}", s.name()), s.description())));
+        filters.extend(
+            [
+                ("Shortage", "Systems with a market short of the commodity"),
+                (
+                    "Industry",
+                    "Systems with a colony that has built the industry or structure",
+                ),
+                (
+                    "Mission Faction",
+                    "Systems with missions offered by the faction",
+                ),
+                (
+                    "Region",
+                    "Systems in the constellation, or quarter of the sector",
+                ),
+                (
+                    "Colony Size",
+                    "Systems with a colony of at least the planet size",
+                ),
+                ("Market Size", "Systems with a market of at least the size"),
+                ("Atmosphere", "Systems with a planet of the atmosphere"),
+                ("Temperature", "Systems with a planet of the temperature"),
+                (
+                    "Stars",
+                    "Systems with a single star, a binary pair, or three or more",
+                ),
+                (
+                    "Remnant Danger",
+                    "Systems whose warning beacon is at least as dire",
+                ),
+                (
+                    "Black Holes & Pulsars",
+                    "Systems with, or without, a black hole or pulsar",
+                ),
+                (
+                    "Nearest Resource",
+                    "The closest systems to the selected one with the resource",
+                ),
+            ]
+            .map(|(filter, meaning)| (filter.to_string(), meaning)),
+        );
+        for (filter, meaning) in filters {
+            ui.label(filter);
+            ui.label(meaning);
+            ui.end_row();
+        }
+    });
 
-    Some(Object {
-        name: what
-            .entries()
-            .find(|e| e.0 == "f0")?
-            .1
-            .as_str()?
-            .to_string(),
-        planet: false,
-        pos: loc,
-        mission,
-    })
+    ui.separator();
+    ui.strong("Shortcuts");
+    egui::Grid::new("help_shortcuts").show(ui, |ui| {
+        let shortcuts = [
+            ("Escape", "Clear the filter"),
+            ("1", "Show the current system"),
+            ("2", "Show the sector map"),
+            ("3", "Show or hide the object table"),
+            ("Ctrl-click a system", "Mark it for export"),
+            (
+                "Mouse back and forward",
+                "Go back and forth through systems visited",
+            ),
+            ("Click an object", "Show its details"),
+            (
+                "Double-click the plot",
+                "Fit the view to the system, unless it's locked",
+            ),
+            (
+                "Right-click an object",
+                "Copy its details, centre on it, measure from it or favourite it",
+            ),
+            (
+                "Ctrl + and Ctrl -",
+                "Zoom the interface in and out, with Ctrl 0 to reset",
+            ),
+        ];
+        for (keys, action) in shortcuts {
+            ui.strong(keys);
+            ui.label(action);
+            ui.end_row();
+        }
+    });
 }
 
-#[derive(Debug, Default)]
-struct ScanSectorUi {
-    pick_file: Option<JoinHandle<Option<PathBuf>>>,
-    message: Option<String>,
-    save: Option<PathBuf>,
-    systems: Vec<System>,
-    filter: String,
-    lc_filter: String,
-    mission_only: bool,
-    selected: usize,
+fn terrain_color(kind: TerrainKind) -> Color32 {
+    match kind {
+        TerrainKind::Nebula => Color32::from_rgb(0x9b, 0x4c, 0xe8),
+        TerrainKind::AsteroidBelt => Color32::from_rgb(0x9b, 0x80, 0x60),
+        TerrainKind::Ring => Color32::from_rgb(0x80, 0x9b, 0xb0),
+    }
 }
 
-impl ScanSectorUi {
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self::default()
+/// A copy of `system` with everything moved so `origin` sits at (0, 0).
+fn recentred(system: &System, origin: Position) -> System {
+    let shift = |pos: &mut Position| {
+        pos.x -= origin.x;
+        pos.y -= origin.y;
+    };
+    let mut system = system.clone();
+    for object in &mut system.objects {
+        shift(&mut object.pos);
+        for ring in &mut object.rings {
+            shift(&mut ring.center);
+        }
+    }
+    for terrain in &mut system.terrain {
+        shift(&mut terrain.center);
     }
+    system
 }
 
-impl eframe::App for ScanSectorUi {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        egui::gui_zoom::zoom_with_keyboard_shortcuts(ctx, frame.info().native_pixels_per_point);
+/// Convex polygons approximating a terrain region: the disc itself, or the quads making up a
+/// ring, since the plot can only fill convex shapes.
+fn terrain_shapes(terrain: &Terrain) -> Vec<Vec<[f64; 2]>> {
+    const SEGMENTS: usize = 64;
 
-        egui::TopBottomPanel::top("footer").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.menu_button("View", |ui| {
-                    egui::gui_zoom::zoom_menu_buttons(ui, frame.info().native_pixels_per_point);
+    let point = |radius: f64, segment: usize| {
+        let angle = std::f64::consts::TAU * segment as f64 / SEGMENTS as f64;
+        [
+            terrain.center.x + radius * angle.cos(),
+            terrain.center.y + radius * angle.sin(),
+        ]
+    };
+    let (inner, outer) = (terrain.inner_radius, terrain.outer_radius);
 
-                    egui::widgets::global_dark_light_mode_buttons(ui);
-                });
+    if inner <= 0.0 {
+        return vec![(0..SEGMENTS).map(|i| point(outer, i)).collect()];
+    }
 
-                if self
-                    .pick_file
-                    .as_ref()
-                    .map(|t| t.is_finished())
-                    .unwrap_or(false)
-                {
-                    let jh = self.pick_file.take().unwrap();
-                    self.save = jh.join().unwrap();
+    (0..SEGMENTS)
+        .map(|i| {
+            vec![
+                point(inner, i),
+                point(outer, i),
+                point(outer, i + 1),
+                point(inner, i + 1),
+            ]
+        })
+        .collect()
+}
 
-                    if let Some(path) = &self.save {
-                        match load_save(path) {
-                            Ok(systems) => {
-                                self.systems = systems;
-                                self.message = None;
-                            }
-                            Err(e) => {
-                                self.message = Some(e.to_string());
-                            }
-                        }
-                    }
-                }
+fn render_system(
+    ui: &mut Ui,
+    system: &System,
+    options: &PlotOptions,
+    state: &mut PlotState,
+    favorites: &BTreeSet<ObjectKey>,
+    visibility: &Visibility,
+    names: NameStyle,
+) -> Option<PlotAction> {
+    let hidden = objects_among(system, &state.hidden);
+    let favorites = objects_among(system, favorites);
+    let visible =
+        |index: usize| visibility.shows(&system.objects[index]) && !hidden.contains(&index);
+    let mut action = None;
 
-                ui.add_enabled_ui(self.pick_file.is_none(), |ui| {
-                    if ui.button("Pick Save").clicked() {
-                        self.pick_file = Some(std::thread::spawn(move || {
-                            rfd::FileDialog::new()
-                                .add_filter("XML", &["xml"])
-                                .pick_file()
-                        }));
-                    }
-                });
+    let origin = system
+        .star()
+        .map(|(star, _)| star.pos)
+        .filter(|_| options.star_relative);
+    let relative;
+    let system = match origin {
+        Some(origin) => {
+            relative = recentred(system, origin);
+            &relative
+        }
+        None => system,
+    };
+    if let Some(index) = state.center_on_object.take() {
+        state.center_on = system.objects.get(index).map(|o| o.pos);
+    }
 
-                if let Some(path) = &self.save {
-                    ui.heading(path.to_string_lossy());
+    ui.horizontal(|ui| {
+        ui.heading(format!("Current System: {}", names.system(system)));
+        if ui
+            .button("Reset View")
+            .on_hover_text("Frame all visible objects, as does double-clicking an unlocked plot")
+            .clicked()
+        {
+            state.reset = true;
+        }
+        ui.checkbox(&mut state.locked, "🔒 Lock View")
+            .on_hover_text("Keep the zoom and centre when switching systems or double-clicking");
+
+        ComboBox::from_id_source("_focus_category")
+            .selected_text(match state.focus {
+                Some(category) => format!("Focus: {}", category.name()),
+                None => "Focus: None".to_string(),
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut state.focus, None, "None");
+                for category in Category::ALL {
+                    ui.selectable_value(&mut state.focus, Some(category), category.name());
                 }
+            })
+            .response
+            .on_hover_text("Fade everything but one kind of object, keeping the rest for context");
+
+        if let Some((_, class)) = system.star() {
+            ui.separator();
+            ui.label(format!(
+                "{}, solar power: {}",
+                class.name(),
+                class.solar_power().name()
+            ));
+        }
+        for hazard in system.stellar_hazards() {
+            ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {}", hazard.name()))
+                .on_hover_text(format!("A system-wide hazard: {}", hazard.effect()));
+        }
+        // A lone star goes without saying
+        if let Some(multiplicity) = system.multiplicity().filter(|&m| m != Multiplicity::Single) {
+            ui.label(format!(
+                "{} system of {} stars",
+                multiplicity.name(),
+                system.star_count()
+            ));
+        }
+
+        let missions = system.missions().count();
+        if missions > 0 {
+            ui.separator();
+            let factions: Vec<&str> = system
+                .mission_factions()
+                .into_iter()
+                .map(faction_name)
+                .collect();
+            ui.label(match (missions, factions.is_empty()) {
+                (1, true) => "1 mission".to_string(),
+                (n, true) => format!("{n} missions"),
+                (1, false) => format!("1 mission from {}", factions.join(", ")),
+                (n, false) => format!("{n} missions from {}", factions.join(", ")),
             });
-        });
+        }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if let Some(message) = &self.message {
-                ui.label(message.clone());
+        let stable_locations = system.stable_locations();
+        if stable_locations > 0 {
+            ui.separator();
+            ui.label(format!(
+                "Stable locations: {} free of {stable_locations}",
+                system.free_stable_locations()
+            ))
+            .on_hover_text("Built on by comm relays, nav buoys and sensor arrays");
+        }
+
+        let infrastructure: Vec<String> = system
+            .objects
+            .iter()
+            .filter_map(|o| {
+                let kind = o.infrastructure()?;
+                let name = if o.domain_era() {
+                    format!("Domain {}", kind.name().to_lowercase())
+                } else {
+                    kind.name().to_string()
+                };
+                Some(match o.faction.as_deref().filter(|_| o.operational()) {
+                    Some(faction) => format!("{name} ({})", faction_name(faction)),
+                    None => format!("{name} (unclaimed)"),
+                })
+            })
+            .collect();
+        if !infrastructure.is_empty() {
+            ui.separator();
+            ui.label(infrastructure.join(", "));
+        }
+
+        if let Some(region) = system.region() {
+            ui.separator();
+            ui.label(region);
+        }
+
+        if let Some(location) = system.location {
+            ui.separator();
+            ui.label(format!("Hyperspace: {:.0}, {:.0}", location.x, location.y));
+            if ui
+                .small_button("📋")
+                .on_hover_text("Copy as x|y, as vectors are written in saves and the console")
+                .clicked()
+            {
+                ui.output_mut(|o| o.copied_text = vector_text(location));
             }
+        }
+    });
 
-            if !self.systems.is_empty() {
-                ui.group(|ui| {
-                    ui.heading("Select a System");
-                    ui.horizontal(|ui| {
-                        ui.label("Filter");
-                        if ui.text_edit_singleline(&mut self.filter).changed() {
-                            self.lc_filter = self.filter.to_lowercase();
-                            if !self.systems[self.selected]
-                                .lc_name
-                                .contains(&self.lc_filter)
-                            {
-                                self.selected = self
-                                    .systems
-                                    .iter()
-                                    .position(|sys| sys.lc_name.contains(&self.lc_filter))
-                                    .unwrap_or(self.selected);
-                            }
-                        }
+    // Counts of every object, visible or not, doubling as toggles for each category
+    ui.horizontal(|ui| {
+        for category in Category::ALL {
+            let count = system
+                .objects
+                .iter()
+                .filter(|o| o.category() == category)
+                .count();
+            if count == 0 {
+                continue;
+            }
 
-                        ui.checkbox(&mut self.mission_only, "Mission Only");
-
-                        ComboBox::from_id_source("_star_system_select")
-                            .width(ui.available_width())
-                            .selected_text(self.systems[self.selected].name.clone())
-                            .show_ui(ui, |ui| {
-                                for (index, system) in self.systems.iter().enumerate() {
-                                    if (!self.mission_only || system.mission) && system.lc_name.contains(&self.lc_filter) {
-                                        ui.selectable_value(
-                                            &mut self.selected,
-                                            index,
-                                            &system.name,
-                                        );
-                                    }
-                                }
-                            });
-                    });
-                });
+            let shown = visibility.shows_category(category);
+            let label = format!("{}: {count}", category.name());
+            if ui
+                .selectable_label(shown, label)
+                .on_hover_text(if shown {
+                    "Click to hide"
+                } else {
+                    "Click to show"
+                })
+                .clicked()
+            {
+                action = Some(PlotAction::ToggleCategory(category));
+            }
+        }
 
-                render_system(ui, &self.systems[self.selected]);
+        if !system.fleets.is_empty() {
+            ui.separator();
+            ui.label(match system.remnant_fleets() {
+                0 => format!("Fleets: {}", system.fleets.len()),
+                remnants => format!("Fleets: {} ({remnants} remnant)", system.fleets.len()),
+            });
+        }
+        let stations = system.remnant_stations();
+        if stations > 0 || system.danger().is_some() {
+            ui.separator();
+            let mut text = match system.danger() {
+                Some(danger) => format!("☠ Remnant danger: {}", danger.name()),
+                None => "☠ Remnant".to_string(),
+            };
+            match stations {
+                0 => {}
+                1 => text.push_str(", 1 battlestation"),
+                stations => text.push_str(&format!(", {stations} battlestations")),
             }
-        });
-    }
-}
+            ui.colored_label(ui.visuals().warn_fg_color, text);
+        }
+    });
 
-fn render_system(ui: &mut Ui, system: &System) {
-    ui.heading(format!("Current System: {}", system.name));
+    let search = state.search.to_lowercase();
+    let matches =
+        |object: &Object| search.is_empty() || object.name.to_lowercase().contains(&search);
 
-    if system.objects.is_empty() {
+    let objects: Vec<(usize, &Object)> = system
+        .objects
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| visible(index))
+        .collect();
+
+    if objects.is_empty() {
         ui.label("Spooky empty system");
-        return;
+        return action;
     }
 
-    let bounds_x = system
-        .objects
-        .iter()
-        .map(|s| s.pos.x.abs())
-        .reduce(f64::max)
-        .unwrap()
-        + 2000.0;
-    let bounds_y = system
-        .objects
-        .iter()
-        .map(|s| s.pos.y.abs())
-        .reduce(f64::max)
-        .unwrap()
-        + 2000.0;
+    ui.horizontal(|ui| {
+        ui.label("Search");
+        ui.text_edit_singleline(&mut state.search);
+        if !search.is_empty() {
+            let count = objects.iter().filter(|(_, o)| matches(o)).count();
+            ui.label(match count {
+                1 => "1 match".to_string(),
+                count => format!("{count} matches"),
+            });
+        }
+    });
+
+    let dense = objects.len() > DENSE_SYSTEM_OBJECTS;
+    if dense && options.show_labels {
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "⚠ {} objects shown, so labels are hidden: hover over objects for their names",
+                    objects.len()
+                ),
+            );
+            ui.checkbox(&mut state.dense_labels, "Show Anyway");
+        });
+    }
+    let show_labels = options.show_labels && (!dense || state.dense_labels);
+
+    // Reach to the edge of each object rather than its centre, so a lone star isn't clipped
+    let extent = |axis: fn(&Position) -> f64| {
+        objects
+            .iter()
+            .map(|(_, o)| axis(&o.pos).abs() + o.radius.unwrap_or(0.0))
+            .fold(0.0, f64::max)
+    };
+    let (extent_x, extent_y) = (extent(|p| p.x), extent(|p| p.y));
+    // Pad by a share of the whole span, so tight moon clusters aren't lost in empty space
+    let padding = (2.0 * extent_x.max(extent_y) * options.padding).max(MIN_PADDING);
+    // Frame to the plot's own shape up front, so egui has no reason to adjust the bounds
+    // and the frame is the same from one frame to the next
+    let size = ui.available_size_before_wrap();
+    let (bounds_x, bounds_y) = fit_aspect(
+        (extent_x + padding).max(MIN_VIEW_EXTENT),
+        (extent_y + padding).max(MIN_VIEW_EXTENT),
+        f64::from(size.x / size.y.max(1.0)),
+    );
 
-    use eframe::egui::plot::{Legend, MarkerShape, Plot, Points};
-    let plot = Plot::new("system_display")
+    use eframe::egui::plot::{
+        CoordinatesFormatter, Corner, Legend, Line, Plot, PlotBounds, Points, Polygon,
+    };
+    let mut plot = Plot::new("system_display")
         .data_aspect(1.0)
         .include_x(bounds_x)
         .include_x(-bounds_x)
         .include_y(bounds_y)
         .include_y(-bounds_y)
-        .auto_bounds_x()
-        .auto_bounds_y()
+        .allow_double_click_reset(!state.locked)
+        .coordinates_formatter(
+            Corner::LeftBottom,
+            CoordinatesFormatter::new(|pointer, _| format!("{:.0}, {:.0}", pointer.x, pointer.y)),
+        )
         .legend(Legend::default());
+    if std::mem::take(&mut state.reset) {
+        plot = plot.reset();
+        state.pan = None;
+    }
+
+    let mut clicked = None;
+    let response = plot.show(ui, |ui| {
+        if state.locked {
+            // Claim the view as the user's, so the frame isn't applied to a new system
+            ui.set_plot_bounds(ui.plot_bounds());
+        }
+        let time = ui.ctx().input(|i| i.time);
+        if let Some(to) = state.center_on.take() {
+            let from = ui.plot_bounds().center();
+            state.pan = Some(Pan {
+                from: Position {
+                    x: from.x,
+                    y: from.y,
+                },
+                to,
+                started: time,
+            });
+        }
+        // Dragging or zooming takes over from a slide still going
+        if ui.plot_hovered() && ui.pointer_coordinate_drag_delta() != egui::Vec2::ZERO {
+            state.pan = None;
+        }
+        if let Some(pan) = state.pan {
+            let (center, done) = pan.at(time);
+            let bounds = ui.plot_bounds();
+            let (half_width, half_height) = (bounds.width() / 2.0, bounds.height() / 2.0);
+            ui.set_plot_bounds(PlotBounds::from_min_max(
+                [center.x - half_width, center.y - half_height],
+                [center.x + half_width, center.y + half_height],
+            ));
+            if done {
+                state.pan = None;
+            } else {
+                ui.ctx().request_repaint();
+            }
+        }
+
+        if options.show_terrain {
+            for terrain in &system.terrain {
+                for polygon in terrain_shapes(terrain) {
+                    ui.polygon(
+                        Polygon::new(polygon)
+                            .color(terrain_color(terrain.kind))
+                            .width(0.0),
+                    );
+                }
+            }
+        }
+
+        for ring in objects.iter().flat_map(|(_, o)| &o.rings) {
+            for polygon in terrain_shapes(ring) {
+                ui.polygon(
+                    Polygon::new(polygon)
+                        .color(terrain_color(ring.kind))
+                        .width(0.0),
+                );
+            }
+        }
+
+        if options.show_orbits {
+            for (_, object) in &objects {
+                let Some(parent) = object.parent else { continue };
+                if visible(parent) {
+                    let parent = &system.objects[parent];
+                    ui.line(
+                        Line::new(vec![
+                            [object.pos.x, object.pos.y],
+                            [parent.pos.x, parent.pos.y],
+                        ])
+                        .color(Color32::GRAY)
+                        .width(1.0),
+                    );
+                }
+            }
+        }
+
+        // Trails are in the saves' own coordinates, so move them with the system
+        let shift = origin.unwrap_or(Position { x: 0.0, y: 0.0 });
+        for &(_, object) in &objects {
+            let Some(trail) = state.trails.get(object.trail_key()) else { continue };
+            let points: Vec<[f64; 2]> = trail
+                .iter()
+                .map(|p| [p.x - shift.x, p.y - shift.y])
+                .collect();
+            let color = options.color(object).gamma_multiply(TRAIL_FADE);
+            ui.line(Line::new(points.clone()).color(color).width(1.0));
+            ui.points(Points::new(points).color(color).radius(2.0));
+        }
+
+        // New and changed objects glow, and those that are gone flash out where they were
+        for change in &state.changes {
+            let hidden_change = match system
+                .objects()
+                .find(|(_, o)| o.trail_key() == change.key && o.pos == change.pos)
+            {
+                Some((index, _)) => hidden.contains(&index),
+                // Gone from the system, but hidden while it was there
+                None => state
+                    .hidden
+                    .iter()
+                    .any(|(s, key, _)| *s == system.name && *key == change.key),
+            };
+            if hidden_change {
+                continue;
+            }
+            let fade = state.change_fade.clamp(0.0, 1.0);
+            let (color, shape, radius) = match change.change {
+                Change::Appeared => (Color32::GREEN, MarkerShape::Circle, 20.0),
+                Change::Changed => (Color32::YELLOW, MarkerShape::Circle, 16.0),
+                Change::Disappeared => (Color32::RED, MarkerShape::Cross, 10.0 + 10.0 * fade),
+            };
+            let label = format!("{} ({})", change.name, change.change.name().to_lowercase());
+            ui.points(
+                Points::new(vec![[change.pos.x - shift.x, change.pos.y - shift.y]])
+                    .name(label)
+                    .shape(shape)
+                    .radius(radius)
+                    .color(color.gamma_multiply(0.5 * fade)),
+            );
+        }
+
+        // Lines out from the reference object to every other, labelled halfway along
+        let reference = state
+            .distances_from
+            .as_ref()
+            .filter(|(s, ..)| *s == system.name)
+            .and_then(|(_, key, nth)| system.find_object(key, *nth))
+            .and_then(|from| objects.iter().find(|(index, _)| *index == from));
+        if let Some(&(from, hub)) = reference {
+            let color = ui.ctx().style().visuals.text_color();
+            for &(_, object) in objects.iter().filter(|(index, _)| *index != from) {
+                let (dx, dy) = (object.pos.x - hub.pos.x, object.pos.y - hub.pos.y);
+                ui.line(
+                    Line::new(vec![[hub.pos.x, hub.pos.y], [object.pos.x, object.pos.y]])
+                        .color(color.gamma_multiply(0.2))
+                        .width(1.0),
+                );
+                ui.text(Text::new(
+                    PlotPoint::new(hub.pos.x + dx / 2.0, hub.pos.y + dy / 2.0),
+                    RichText::new(state.units.format(dx.hypot(dy)))
+                        .size(options.label_size * 0.8)
+                        .color(color.gamma_multiply(0.7)),
+                ));
+            }
+        }
 
-    plot.show(ui, |ui| {
-        for object in &system.objects {
+        for &(index, object) in &objects {
+            let (radius, mut fade) = match (search.is_empty(), matches(object)) {
+                (true, _) => (10.0, 1.0),
+                (false, true) => (13.0, 1.0),
+                (false, false) => (8.0, 0.25),
+            };
+            if state.focus.is_some_and(|focus| object.category() != focus) {
+                fade *= 0.15;
+            }
+            // Gates yet to be activated are drawn hollow
             let points = Points::new(vec![[object.pos.x, object.pos.y]])
-                .name(object.name.to_string())
-                .filled(true)
-                .radius(10.0)
-                .shape(if object.planet {
-                    MarkerShape::Circle
-                } else if object.mission {
-                    MarkerShape::Asterisk
-                } else {
-                    MarkerShape::Cross
-                });
+                .name(names.object(object))
+                .filled(object.gate_state() != Some(GateState::Inactive))
+                .radius(radius)
+                .color(options.color(object).gamma_multiply(fade))
+                .shape(marker_shape(system, index, options));
 
             ui.points(points);
-            ui.text(
-                Text::new(PlotPoint::new(object.pos.x, object.pos.y), &object.name)
+            if show_labels {
+                let favorite = favorites.contains(&index);
+                let name = shorten(names.object(object));
+                let label = if favorite {
+                    format!("★ {name}")
+                } else {
+                    name.into_owned()
+                };
+                ui.text(
+                    Text::new(
+                        PlotPoint::new(object.pos.x, object.pos.y),
+                        RichText::new(label)
+                            .size(options.label_size)
+                            .color(ui.ctx().style().visuals.text_color().gamma_multiply(fade)),
+                    )
                     .name(object.name.clone()),
-            );
+                );
+            }
+        }
+
+        let pointed = || {
+            ui.pointer_coordinate()
+                .and_then(|pointer| object_at(ui, &objects, pointer))
+        };
+        if ui.plot_clicked() {
+            clicked = pointed();
+        }
+        if ui.plot_secondary_clicked() {
+            state.context_object = pointed();
         }
     });
+
+    if let Some(index) = clicked {
+        action = Some(PlotAction::SelectObject(index));
+    }
+
+    if let Some((index, object)) = state
+        .context_object
+        .and_then(|i| Some((i, system.objects.get(i)?)))
+    {
+        response.response.context_menu(|ui| {
+            ui.strong(&object.name);
+            ui.separator();
+
+            if ui.button("Copy Name").clicked() {
+                ui.output_mut(|o| o.copied_text = object.name.clone());
+                ui.close_menu();
+            }
+            if ui.button("Copy Coordinates").clicked() {
+                ui.output_mut(|o| {
+                    o.copied_text = format!("{:.0}, {:.0}", object.pos.x, object.pos.y)
+                });
+                ui.close_menu();
+            }
+            if ui.button("Copy as x|y").clicked() {
+                ui.output_mut(|o| o.copied_text = vector_text(object.pos));
+                ui.close_menu();
+            }
+            if ui.button("Centre View Here").clicked() {
+                state.center_on = Some(object.pos);
+                ui.close_menu();
+            }
+            let key = object_key(system, index);
+            if state.distances_from.as_ref() == Some(&key) {
+                if ui.button("Hide Distances").clicked() {
+                    state.distances_from = None;
+                    ui.close_menu();
+                }
+            } else if ui
+                .button("Show Distances From Here")
+                .on_hover_text("Label every other object with how far it is from this one")
+                .clicked()
+            {
+                state.distances_from = Some(key);
+                ui.close_menu();
+            }
+
+            let label = if favorites.contains(&index) {
+                "Remove Favourite"
+            } else {
+                "Mark Favourite"
+            };
+            if ui.button(label).clicked() {
+                action = Some(PlotAction::ToggleFavorite(index));
+                ui.close_menu();
+            }
+        });
+    }
+
+    action
+}
+
+/// Command-line arguments, for opening a save with a given system selected, or exporting one
+/// without opening a window at all.
+#[derive(Debug, Default)]
+struct Args {
+    save: Option<PathBuf>,
+    system: Option<String>,
+    /// Read the save from standard input rather than a file.
+    stdin: bool,
+    /// Where to write a JSON export, `-` being standard output.
+    export_json: Option<PathBuf>,
+    /// Where to write a CSV export, `-` being standard output.
+    export_csv: Option<PathBuf>,
+}
+
+impl Args {
+    const USAGE: &str = "Usage: scansector [SAVE] [--system NAME]\n       \
+                         scansector (SAVE | --stdin) [--export-json PATH] [--export-csv PATH]";
+
+    /// Whether anything's to be exported, and no window opened.
+    fn headless(&self) -> bool {
+        self.export_json.is_some() || self.export_csv.is_some()
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--system" => {
+                    parsed.system = Some(args.next().ok_or("--system needs a system name")?);
+                }
+                _ if arg.starts_with("--system=") => {
+                    parsed.system = Some(arg["--system=".len()..].to_string());
+                }
+                "--stdin" => parsed.stdin = true,
+                "--export-json" => {
+                    let path = args
+                        .next()
+                        .ok_or("--export-json needs a path, or - for stdout")?;
+                    parsed.export_json = Some(path.into());
+                }
+                "--export-csv" => {
+                    let path = args
+                        .next()
+                        .ok_or("--export-csv needs a path, or - for stdout")?;
+                    parsed.export_csv = Some(path.into());
+                }
+                // A lone dash is standard output, never a save
+                "-" => return Err("Use --stdin to read a save from standard input".to_string()),
+                _ if arg.starts_with('-') => return Err(format!("Unknown option {arg}")),
+                _ if parsed.save.is_none() => parsed.save = Some(arg.into()),
+                _ => return Err(format!("Unexpected argument {arg}")),
+            }
+        }
+
+        if parsed.system.is_some() && parsed.save.is_none() {
+            return Err("--system needs a save to select it in".to_string());
+        }
+        if parsed.stdin && parsed.save.is_some() {
+            return Err("Give either a save or --stdin, not both".to_string());
+        }
+        if parsed.stdin && !parsed.headless() {
+            return Err("--stdin needs an export to write".to_string());
+        }
+        if parsed.headless() && parsed.save.is_none() && !parsed.stdin {
+            return Err("Exporting needs a save, or --stdin".to_string());
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Load the save named by `args` and write the exports asked for, without any UI.
+fn export_headless(args: &Args) -> Result<(), String> {
+    let sector = match &args.save {
+        Some(path) => load_sector(path),
+        None => read_sector(std::io::stdin().lock()),
+    }
+    .map_err(|e| e.to_string())?;
+    // Parts of the save that couldn't be read, which the window would warn of
+    for problem in &sector.problems {
+        eprintln!("{problem}");
+    }
+
+    let exports = [
+        (
+            &args.export_json,
+            export::to_json as fn(&[System]) -> String,
+        ),
+        (&args.export_csv, export::to_csv),
+    ];
+    for (path, to_text) in exports {
+        let Some(path) = path else { continue };
+        let text = to_text(&sector.systems);
+        if path.as_os_str() == "-" {
+            std::io::stdout()
+                .lock()
+                .write_all(text.as_bytes())
+                .map_err(|e| format!("Failed to write to standard output: {e}"))?;
+        } else {
+            std::fs::write(path, text)
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        }
+    }
+    Ok(())
 }
 
 fn main() {
+    let args = Args::parse(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{e}\n{}", Args::USAGE);
+        std::process::exit(2);
+    });
+
+    if args.headless() {
+        if let Err(e) = export_headless(&args) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
         "Scansector - Starsector System Scanner",
         native_options,
-        Box::new(|cc| Box::new(ScanSectorUi::new(cc))),
+        Box::new(|cc| Box::new(ScanSectorUi::new(cc, args))),
     )
     .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Args, ScanSectorUi};
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn opens_a_save_at_a_system() {
+        let args = parse(&["Save.xml", "--system=Corvus"]).unwrap();
+        assert_eq!(args.save.as_deref(), Some(std::path::Path::new("Save.xml")));
+        assert_eq!(args.system.as_deref(), Some("Corvus"));
+        assert!(!args.headless());
+    }
+
+    #[test]
+    fn exports_from_standard_input() {
+        let args = parse(&["--stdin", "--export-csv", "-"]).unwrap();
+        assert!(args.stdin && args.save.is_none() && args.headless());
+    }
+
+    #[test]
+    fn refuses_a_save_and_standard_input_together() {
+        let error = parse(&["Save.xml", "--stdin", "--export-json", "out.json"]).unwrap_err();
+        assert_eq!(error, "Give either a save or --stdin, not both");
+    }
+
+    #[test]
+    fn refuses_a_dash_for_the_save() {
+        let error = parse(&["-", "--export-csv", "out.csv"]).unwrap_err();
+        assert_eq!(error, "Use --stdin to read a save from standard input");
+    }
+
+    #[test]
+    fn refuses_a_system_without_a_save() {
+        let error = parse(&["--system", "Corvus"]).unwrap_err();
+        assert_eq!(error, "--system needs a save to select it in");
+    }
+
+    #[test]
+    fn refuses_to_export_without_a_save() {
+        let error = parse(&["--export-json", "out.json"]).unwrap_err();
+        assert_eq!(error, "Exporting needs a save, or --stdin");
+    }
+
+    #[test]
+    fn refuses_standard_input_without_an_export() {
+        let error = parse(&["--stdin"]).unwrap_err();
+        assert_eq!(error, "--stdin needs an export to write");
+    }
+
+    #[test]
+    fn reloading_keeps_pins_marks_and_history() {
+        let save = PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/campaign.xml"
+        ));
+        let mut ui = ScanSectorUi::default();
+        assert!(ui.load(save.clone()));
+
+        ui.selected = 1;
+        ui.selected_object = Some(0);
+        ui.pinned = Some((0, 0));
+        ui.marked.insert(2);
+        ui.history.visit(0);
+        assert!(ui.load(save));
+
+        assert_eq!(ui.selected, 1);
+        assert_eq!(ui.selected_object, Some(0));
+        assert_eq!(ui.pinned, Some((0, 0)));
+        assert!(ui.marked.contains(&2));
+        assert_eq!(ui.history.back(ui.selected), Some(0));
+    }
+
+    #[test]
+    fn a_save_that_wont_open_leaves_the_open_one_alone() {
+        let fixtures = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures"));
+        let mut ui = ScanSectorUi::default();
+        assert!(ui.load(fixtures.join("campaign.xml")));
+        ui.notes
+            .insert("Corvus".to_string(), "Survey the gas giant".to_string());
+
+        assert!(!ui.load(fixtures.join("missing.xml")));
+        assert_eq!(ui.save, Some(fixtures.join("campaign.xml")));
+        assert_eq!(ui.systems.len(), 3);
+        assert_eq!(ui.notes["Corvus"], "Survey the gas giant");
+        assert!(ui.saves.is_empty());
+    }
+}
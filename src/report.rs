@@ -0,0 +1,180 @@
+//! A human-readable Markdown summary of a save, for sharing.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::{
+    colony_candidates, faction_name, home_world_candidates, survey_progress, territory, Descriptor,
+    Resource, System,
+};
+
+/// How many entries each section of the report lists at most.
+const LIMIT: usize = 5;
+
+/// Summarize a save as Markdown, from its descriptor, systems and faction relationships.
+pub fn to_markdown(
+    descriptor: &Descriptor,
+    systems: &[System],
+    relationships: &BTreeMap<String, f64>,
+) -> String {
+    let mut report = String::new();
+    // Writing to a String can't fail
+    let _ = write_report(&mut report, descriptor, systems, relationships);
+    report
+}
+
+/// The best `limit` colony candidates as a Markdown table, ranked by score.
+pub fn colony_table(systems: &[System], limit: usize) -> String {
+    let mut table = String::new();
+    let _ = write_colony_table(&mut table, systems, limit);
+    table
+}
+
+fn write_colony_table(out: &mut String, systems: &[System], limit: usize) -> std::fmt::Result {
+    writeln!(
+        out,
+        "| # | System | Planet | Type | Hazard | Resources | Score |"
+    )?;
+    writeln!(
+        out,
+        "|--:|--------|--------|------|-------:|-----------|------:|"
+    )?;
+    for (rank, &(system, object, score)) in
+        colony_candidates(systems).iter().take(limit).enumerate()
+    {
+        let system = &systems[system];
+        let object = &system.objects[object];
+        let Some(market) = &object.market else { continue };
+        let resources: Vec<String> = market
+            .resources()
+            .map(|(resource, grade)| {
+                format!("{} ({})", resource.name(), resource.grade_name(grade))
+            })
+            .collect();
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {:.0}% | {} | {score:.0} |",
+            rank + 1,
+            cell(&system.name),
+            cell(&object.name),
+            cell(object.planet_type.as_deref().unwrap_or("")),
+            market.hazard() * 100.0,
+            resources.join(", "),
+        )?;
+    }
+    Ok(())
+}
+
+/// Escape the pipes that would otherwise end a table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn write_report(
+    out: &mut String,
+    descriptor: &Descriptor,
+    systems: &[System],
+    relationships: &BTreeMap<String, f64>,
+) -> std::fmt::Result {
+    match &descriptor.character_name {
+        Some(name) => writeln!(out, "# {name}'s Sector")?,
+        None => writeln!(out, "# Sector Summary")?,
+    }
+    writeln!(out)?;
+
+    if let Some(date) = &descriptor.save_date {
+        writeln!(out, "- Saved: {date}")?;
+    }
+    if let Some(version) = &descriptor.game_version {
+        writeln!(out, "- Game version: {version}")?;
+    }
+    if let Some(credits) = descriptor.credits {
+        writeln!(out, "- Credits: {credits:.0}")?;
+    }
+    if let Some(seed) = &descriptor.seed {
+        writeln!(out, "- Seed: {seed}")?;
+    }
+    writeln!(out, "- Systems: {}", systems.len())?;
+    writeln!(
+        out,
+        "- Objects: {}",
+        systems.iter().map(|s| s.objects.len()).sum::<usize>()
+    )?;
+    writeln!(out, "- Factions: {}", relationships.len())?;
+    let (surveyed, planets) = survey_progress(systems);
+    if planets > 0 {
+        writeln!(out, "- Planets surveyed: {surveyed} of {planets}")?;
+    }
+
+    writeln!(out, "\n## Best Colony Candidates\n")?;
+    let candidates = home_world_candidates(systems);
+    if candidates.is_empty() {
+        writeln!(out, "None found.")?;
+    }
+    for &(system, object, hazard) in candidates.iter().take(LIMIT) {
+        writeln!(
+            out,
+            "- {} in {} ({:.0}% hazard)",
+            systems[system].objects[object].name,
+            systems[system].name,
+            hazard * 100.0
+        )?;
+    }
+
+    writeln!(out, "\n## Territory\n")?;
+    let held = territory(systems);
+    if held.is_empty() {
+        writeln!(out, "No faction controls a system.")?;
+    }
+    for &(faction, count) in &held {
+        writeln!(
+            out,
+            "- {}: {count} {} ({:.0}%)",
+            faction_name(faction),
+            if count == 1 { "system" } else { "systems" },
+            count as f64 / systems.len() as f64 * 100.0
+        )?;
+    }
+
+    writeln!(out, "\n## Notable Resources\n")?;
+    let mut any = false;
+    for resource in Resource::ALL {
+        let Some(best) = systems.iter().filter_map(|s| s.resource(resource)).max() else {
+            continue;
+        };
+        let found: Vec<&str> = systems
+            .iter()
+            .filter(|s| s.resource(resource) == Some(best))
+            .map(|s| s.name.as_str())
+            .take(LIMIT)
+            .collect();
+        writeln!(
+            out,
+            "- {} ({}): {}",
+            resource.name(),
+            resource.grade_name(best),
+            found.join(", ")
+        )?;
+        any = true;
+    }
+    if !any {
+        writeln!(out, "None found.")?;
+    }
+
+    writeln!(out, "\n## Active Missions\n")?;
+    let missions: Vec<_> = systems.iter().filter(|s| s.mission).collect();
+    if missions.is_empty() {
+        writeln!(out, "None.")?;
+    }
+    for system in missions {
+        let objects: Vec<&str> = system
+            .objects
+            .iter()
+            .filter(|o| o.mission)
+            .map(|o| o.name.as_str())
+            .collect();
+        writeln!(out, "- {}: {}", system.name, objects.join(", "))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,244 @@
+//! A PNG sheet of summary cards for a handful of systems, each a small map of the system with
+//! its headline stats beneath, for sharing.
+
+use eframe::egui::epaint::text::{FontDefinitions, Fonts};
+use eframe::egui::epaint::{Color32, FontId, Galley, ImageData, Pos2};
+
+use crate::export::ImageTransform;
+use crate::{faction_name, Object, System};
+
+/// How many cards go across the sheet at most.
+const COLUMNS: usize = 4;
+const CARD_WIDTH: usize = 320;
+const MAP_HEIGHT: usize = 240;
+const CARD_HEIGHT: usize = MAP_HEIGHT + 96;
+/// The gap between cards, and around the edge of the sheet.
+const GAP: usize = 8;
+/// Game units left around the outermost objects on each map.
+const PADDING: f64 = 500.0;
+/// How many resources each card names.
+const RESOURCES: usize = 3;
+
+const BACKGROUND: Color32 = Color32::from_gray(20);
+const CARD: Color32 = Color32::from_gray(36);
+const TEXT: Color32 = Color32::from_gray(220);
+const DIM_TEXT: Color32 = Color32::from_gray(150);
+
+/// Render a card for each of `systems` onto one PNG, with objects drawn the colour `color`
+/// gives them.
+pub fn to_png(
+    systems: &[System],
+    color: impl Fn(&Object) -> Color32,
+) -> Result<Vec<u8>, png::EncodingError> {
+    let columns = systems.len().clamp(1, COLUMNS);
+    let rows = systems.len().div_ceil(columns).max(1);
+    let mut canvas = Canvas::new(
+        GAP + columns * (CARD_WIDTH + GAP),
+        GAP + rows * (CARD_HEIGHT + GAP),
+    );
+
+    let fonts = Fonts::new(1.0, 2048, FontDefinitions::default());
+    let mut texts = vec![];
+    for (index, system) in systems.iter().enumerate() {
+        let left = GAP + index % columns * (CARD_WIDTH + GAP);
+        let top = GAP + index / columns * (CARD_HEIGHT + GAP);
+        canvas.fill(left, top, CARD_WIDTH, CARD_HEIGHT, CARD);
+        draw_map(&mut canvas, system, left, top, &color);
+
+        let mut y = (top + MAP_HEIGHT + 6) as f32;
+        for (line, size, color) in card_lines(system) {
+            let galley = fonts.layout_no_wrap(line, FontId::proportional(size), color);
+            let height = galley.size().y;
+            texts.push((Pos2::new(left as f32 + 10.0, y), galley, left + CARD_WIDTH));
+            y += height + 2.0;
+        }
+    }
+
+    // Glyphs are only rasterized as text is laid out, so the atlas is complete by now
+    if let Some(ImageData::Font(atlas)) = fonts.font_image_delta().map(|delta| delta.image) {
+        for (pos, galley, right) in &texts {
+            canvas.text(pos, galley, &atlas.pixels, atlas.size[0], *right);
+        }
+    }
+
+    canvas.encode()
+}
+
+/// The text under a system's map: its name, then who holds it and what it has to offer.
+fn card_lines(system: &System) -> Vec<(String, f32, Color32)> {
+    let mut lines = vec![(system.name.clone(), 18.0, TEXT)];
+
+    let colonies = system.objects.iter().filter(|o| o.market.is_some()).count();
+    lines.push((
+        match (system.controlling_faction(), colonies) {
+            (Some(faction), 1) => format!("{}, 1 colony", faction_name(faction)),
+            (Some(faction), n) => format!("{}, {n} colonies", faction_name(faction)),
+            (None, _) => "Unclaimed".to_string(),
+        },
+        13.0,
+        DIM_TEXT,
+    ));
+
+    let resources: Vec<String> = system
+        .headline_resources()
+        .into_iter()
+        .take(RESOURCES)
+        .map(|(resource, grade)| format!("{} {}", resource.grade_name(grade), resource.name()))
+        .collect();
+    if !resources.is_empty() {
+        lines.push((resources.join(", "), 13.0, DIM_TEXT));
+    }
+
+    let free = system.free_stable_locations();
+    lines.push((
+        format!(
+            "{} objects, {free} free stable location{}",
+            system.objects.len(),
+            if free == 1 { "" } else { "s" }
+        ),
+        13.0,
+        DIM_TEXT,
+    ));
+    lines
+}
+
+/// Draw the system's objects into the map at the top of the card at `left`, `top`, stars
+/// largest and entities smallest.
+fn draw_map(
+    canvas: &mut Canvas,
+    system: &System,
+    left: usize,
+    top: usize,
+    color: &impl Fn(&Object) -> Color32,
+) {
+    // Square, so as not to stretch the system, and centred across the card
+    let side = MAP_HEIGHT as u32;
+    let transform = ImageTransform::fit(system, PADDING, side, side);
+    let inset = (CARD_WIDTH - MAP_HEIGHT) as f64 / 2.0;
+    for object in &system.objects {
+        let (x, y) = transform.apply(object.pos);
+        let radius = if object.star_class().is_some() {
+            6.0
+        } else if object.market.is_some() || object.kind == crate::Kind::Planet {
+            3.5
+        } else {
+            2.0
+        };
+        canvas.disc(
+            left as f64 + inset + x,
+            top as f64 + y,
+            radius,
+            color(object),
+            (left, top, left + CARD_WIDTH, top + MAP_HEIGHT),
+        );
+    }
+}
+
+/// An opaque RGB image being drawn on.
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 3]>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![rgb(BACKGROUND); width * height],
+        }
+    }
+
+    /// Blend `color` over the pixel at `x`, `y` by `coverage`, after the colour's own alpha.
+    fn blend(&mut self, x: usize, y: usize, color: Color32, coverage: f32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let alpha = coverage.clamp(0.0, 1.0) * f32::from(color.a()) / 255.0;
+        let pixel = &mut self.pixels[y * self.width + x];
+        for (channel, value) in pixel.iter_mut().zip(rgb(color)) {
+            *channel += (value - *channel) * alpha;
+        }
+    }
+
+    fn fill(&mut self, left: usize, top: usize, width: usize, height: usize, color: Color32) {
+        for y in top..top + height {
+            for x in left..left + width {
+                self.blend(x, y, color, 1.0);
+            }
+        }
+    }
+
+    /// A filled circle, anti-aliased, kept within the `(left, top, right, bottom)` clip.
+    fn disc(
+        &mut self,
+        x: f64,
+        y: f64,
+        radius: f64,
+        color: Color32,
+        clip: (usize, usize, usize, usize),
+    ) {
+        let (left, top, right, bottom) = clip;
+        let span = |centre: f64, low: usize, high: usize| {
+            let from = (centre - radius - 1.0).floor().max(low as f64) as usize;
+            let to = (centre + radius + 1.0).ceil().min(high as f64) as usize;
+            from..to
+        };
+        for py in span(y, top, bottom) {
+            for px in span(x, left, right) {
+                let distance = (px as f64 + 0.5 - x).hypot(py as f64 + 0.5 - y);
+                self.blend(px, py, color, (radius + 0.5 - distance) as f32);
+            }
+        }
+    }
+
+    /// Copy the glyphs of `galley` out of the font atlas `coverage`, `atlas_width` texels
+    /// across, with its top left at `pos` and clipped at `right`.
+    fn text(
+        &mut self,
+        pos: &Pos2,
+        galley: &Galley,
+        coverage: &[f32],
+        atlas_width: usize,
+        right: usize,
+    ) {
+        let color = galley.job.sections.first().map_or(TEXT, |s| s.format.color);
+        for glyph in galley.rows.iter().flat_map(|row| &row.glyphs) {
+            let uv = glyph.uv_rect;
+            let left_top = *pos + glyph.pos.to_vec2() + uv.offset;
+            let (x0, y0) = (left_top.x.round() as i64, left_top.y.round() as i64);
+            for v in uv.min[1]..uv.max[1] {
+                for u in uv.min[0]..uv.max[0] {
+                    let x = x0 + i64::from(u - uv.min[0]);
+                    let y = y0 + i64::from(v - uv.min[1]);
+                    if x < 0 || y < 0 || x as usize >= right {
+                        continue;
+                    }
+                    let texel = coverage[usize::from(v) * atlas_width + usize::from(u)];
+                    self.blend(x as usize, y as usize, color, texel);
+                }
+            }
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut png = vec![];
+        let mut encoder = png::Encoder::new(&mut png, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let data: Vec<u8> = self
+            .pixels
+            .iter()
+            .flatten()
+            .map(|&channel| (channel * 255.0).round() as u8)
+            .collect();
+        encoder.write_header()?.write_image_data(&data)?;
+        Ok(png)
+    }
+}
+
+/// A colour's channels from 0 to 1, blended as they're stored rather than in linear light.
+fn rgb(color: Color32) -> [f32; 3] {
+    [color.r(), color.g(), color.b()].map(|c| f32::from(c) / 255.0)
+}
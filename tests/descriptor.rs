@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use scansector::{
+    date_order, find_campaigns, load_descriptor, parse_game_version, parse_save, trails,
+    Descriptor, Position, Progress,
+};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/campaign.xml");
+
+#[test]
+fn game_version() {
+    let descriptor = load_descriptor(Path::new(FIXTURE)).unwrap();
+    assert_eq!(descriptor.game_version.as_deref(), Some("0.96a-RC10"));
+    assert!(!descriptor.untested_version());
+    assert_eq!(descriptor.character_name.as_deref(), Some("Test Pilot"));
+    assert_eq!(descriptor.credits, Some(123456.7));
+    assert_eq!(descriptor.seed.as_deref(), Some("CPAXT-JL4FB-2ZG8D"));
+}
+
+#[test]
+fn parse_versions() {
+    assert_eq!(parse_game_version("0.96a-RC10"), Some((0, 96)));
+    assert_eq!(parse_game_version("0.95.1a-RC6"), Some((0, 95)));
+    assert_eq!(parse_game_version("1.0"), Some((1, 0)));
+    assert_eq!(parse_game_version("dev"), None);
+}
+
+#[test]
+fn dates_are_ordered_by_their_numbers() {
+    assert_eq!(date_order("c.206.10"), [206, 10]);
+    assert!(date_order("c.206.2") < date_order("c.206.10"));
+    assert!(date_order("c.206.12.30") < date_order("c.207.1.1"));
+    assert!(date_order("2023-03-14 12:00:00.0 UTC") < date_order("2023-03-14 12:00:10.0 UTC"));
+}
+
+#[test]
+fn untested_versions() {
+    let descriptor = |v: &str| Descriptor {
+        game_version: Some(v.to_string()),
+        ..Descriptor::default()
+    };
+
+    assert!(descriptor("0.9.1a-RC8").untested_version());
+    assert!(descriptor("1.0a").untested_version());
+    assert!(!descriptor("0.97a-RC11").untested_version());
+    assert!(!Descriptor::default().untested_version());
+}
+
+#[test]
+fn progress() {
+    let progress = Progress::load(Path::new(FIXTURE)).unwrap();
+    assert_eq!(
+        progress.save_date.as_deref(),
+        Some("2023-03-14 12:00:00.0 UTC")
+    );
+    assert_eq!(progress.credits, Some(123456.7));
+    assert_eq!(progress.colonies, 0);
+    // Corvus has an undiscovered station and Empty Space has nothing in it to discover
+    assert_eq!(progress.explored_systems, 1);
+}
+
+#[test]
+fn trails_follow_objects_across_saves() {
+    let save = |x: f64| {
+        let systems = parse_save(&format!(
+            r#"<systems><Sstm bN="Drift"><o>
+              <Plnt><loc>{x}|0</loc><j0>{{"f0":"Moving"}}</j0></Plnt>
+              <Plnt><loc>0|{x}</loc><id>moon_1</id><j0>{{"f0":"Moon {x}"}}</j0></Plnt>
+            </o></Sstm></systems>"#
+        ))
+        .unwrap();
+        Progress::of(&Descriptor::default(), &systems)
+    };
+    let (first, second) = (save(100.0), save(200.0));
+    let mut alone = save(300.0);
+    alone.positions.clear();
+
+    let followed = trails([&first, &alone, &second], "Drift");
+    assert_eq!(followed.len(), 2);
+    assert_eq!(
+        followed["Moving"],
+        [Position { x: 100.0, y: 0.0 }, Position { x: 200.0, y: 0.0 }]
+    );
+    // Followed by id, though its name changed
+    assert_eq!(followed["moon_1"].len(), 2);
+    assert!(trails([&first], "Drift").is_empty());
+}
+
+#[test]
+fn campaigns_in_a_saves_directory() {
+    let saves = std::env::temp_dir().join(format!("scansector-saves-{}", std::process::id()));
+    let fixtures = Path::new(FIXTURE).parent().unwrap();
+    for (save, files) in [
+        ("save_test_pilot", &["campaign.xml", "descriptor.xml"][..]),
+        ("save_unknown", &["campaign.xml"][..]),
+        ("not_a_save", &[][..]),
+    ] {
+        std::fs::create_dir_all(saves.join(save)).unwrap();
+        for file in files {
+            std::fs::copy(fixtures.join(file), saves.join(save).join(file)).unwrap();
+        }
+    }
+
+    let campaigns = find_campaigns(&saves);
+    std::fs::remove_dir_all(&saves).unwrap();
+    let campaigns = campaigns.unwrap();
+
+    let names: Vec<String> = campaigns.iter().map(|c| c.name()).collect();
+    assert_eq!(
+        names,
+        ["Test Pilot (2023-03-14 12:00:00.0 UTC)", "save_unknown"]
+    );
+    assert!(campaigns[0].path.ends_with("save_test_pilot/campaign.xml"));
+}
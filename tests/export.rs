@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use scansector::export::{
+    to_csv, to_dot, to_json, to_overlay_json, ImageTransform, FORMAT_VERSION,
+};
+use scansector::{load_save, parse_save, Position};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/campaign.xml");
+
+#[test]
+fn json_envelope() {
+    let systems = load_save(Path::new(FIXTURE)).unwrap();
+    let exported = json::parse(&to_json(&systems)).unwrap();
+
+    assert_eq!(exported["format"], "scansector");
+    assert_eq!(exported["version"], FORMAT_VERSION);
+    assert!(exported["generator"]
+        .as_str()
+        .unwrap()
+        .starts_with("scansector "));
+    assert_eq!(exported["systems"].len(), 3);
+}
+
+#[test]
+fn json_systems() {
+    let systems = load_save(Path::new(FIXTURE)).unwrap();
+    let exported = json::parse(&to_json(&systems)).unwrap();
+    let corvus = &exported["systems"][1];
+
+    assert_eq!(corvus["name"], "Corvus");
+    assert_eq!(corvus["location"]["x"], -2000.0);
+    assert_eq!(corvus["mission"], true);
+
+    let jangala = &corvus["objects"][1];
+    assert_eq!(jangala["type"], "jungle");
+    assert_eq!(jangala["faction"], "hegemony");
+    assert!(corvus["objects"][2]["faction"].is_null());
+
+    let moon = &corvus["objects"][2];
+    assert_eq!(moon["name"], "Jangala Moon");
+    assert_eq!(moon["kind"], "planet");
+    assert_eq!(moon["orbits"], "Jangala");
+    assert_eq!(moon["position"]["y"], 2900.0);
+
+    assert!(exported["systems"][2]["location"].is_null());
+}
+
+#[test]
+fn overlay_pixels() {
+    let systems = load_save(Path::new(FIXTURE)).unwrap();
+    let corvus = &systems[1];
+    let transform = ImageTransform::fit(corvus, 0.0, 1000, 500);
+
+    // Corvus's furthest object is the Research Station at 9000|9000
+    assert_eq!(transform.apply(Position { x: 0.0, y: 0.0 }), (500.0, 250.0));
+    assert_eq!(
+        transform.apply(Position {
+            x: 9000.0,
+            y: 9000.0
+        }),
+        (1000.0, 0.0)
+    );
+    assert_eq!(
+        transform.apply(Position {
+            x: -9000.0,
+            y: -9000.0
+        }),
+        (0.0, 500.0)
+    );
+
+    let exported = json::parse(&to_overlay_json(corvus, &transform)).unwrap();
+    assert_eq!(exported["system"], "Corvus");
+    assert_eq!(exported["transform"]["width"], 1000);
+    assert_eq!(exported["objects"][0]["name"], "Corvus");
+    assert_eq!(exported["objects"][0]["x"], 500.0);
+    assert_eq!(exported["objects"][0]["y"], 250.0);
+}
+
+#[test]
+fn csv_rows() {
+    let systems = load_save(Path::new(FIXTURE)).unwrap();
+    let csv = to_csv(&systems[1..2]);
+    let mut lines = csv.lines();
+
+    assert_eq!(
+        lines.next(),
+        Some("system,object,kind,x,y,mission,discovered,conditions")
+    );
+    assert_eq!(lines.next(), Some("Corvus,Corvus,planet,0,0,false,true,"));
+    assert_eq!(
+        lines.next(),
+        Some("Corvus,Jangala,planet,-4500.5,2500.25,false,true,habitable;farmland_rich;organics_common")
+    );
+    assert_eq!(csv.lines().count(), 1 + systems[1].objects.len());
+}
+
+#[test]
+fn gate_network_dot() {
+    let gate = r#"<CCEnt><loc>0|0</loc><type>inactive_gate</type><j0>{"f0":"Gate"}</j0></CCEnt>"#;
+    let systems = parse_save(&format!(
+        r#"<systems>
+            <Sstm bN="Askonia"><loc>0|0</loc><o>{gate}</o></Sstm>
+            <Sstm bN="Corvus"><loc>3000|4000</loc><o>{gate}</o></Sstm>
+            <Sstm bN="Gateless"><loc>9000|0</loc><o></o></Sstm>
+            <Sstm bN="Say &quot;Hi&quot;"><o>{gate}</o></Sstm>
+        </systems>"#
+    ))
+    .unwrap();
+
+    let dot = to_dot(&systems, true);
+    assert!(dot.starts_with("graph gates {\n"));
+    assert!(dot.contains("    \"Askonia\" -- \"Corvus\" [label=\"5000\", len=0.50];\n"));
+    assert!(dot.contains("    \"Corvus\" -- \"Say \\\"Hi\\\"\";\n"));
+    assert!(!dot.contains("Gateless"));
+    assert_eq!(dot.matches(" -- ").count(), 3);
+
+    assert!(!to_dot(&systems, false).contains("label"));
+}
@@ -0,0 +1,1273 @@
+use std::path::Path;
+
+use scansector::{
+    all_objects, anomalies, changes, faction_name, home_world_candidates, industry_name, load_save,
+    load_sector, marks_procgen, parse_save, read_sector, read_sector_with, survey_progress,
+    territory, trade_links, unique_structures, unvisited_systems, Anomaly, Atmosphere, Category,
+    Change, Danger, Defense, GateState, HazardSource, Infrastructure, JumpPointKind, Kind, Mission,
+    MissionType, Multiplicity, Object, ParseOptions, Port, Quadrant, Resource, Size, SolarPower,
+    Standing, StarClass, StellarHazard, Supply, SurveyLevel, System, Temperature, TerrainKind,
+    Unique, HYPERSPACE_TILE_SIZE,
+};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/campaign.xml");
+
+fn fixture() -> Vec<System> {
+    load_save(Path::new(FIXTURE)).expect("fixture should load")
+}
+
+fn object<'a>(system: &'a System, name: &str) -> &'a Object {
+    system
+        .objects
+        .iter()
+        .find(|o| o.name == name)
+        .unwrap_or_else(|| panic!("{name} not found in {}", system.name))
+}
+
+#[test]
+fn systems_are_sorted_by_name() {
+    let systems = fixture();
+    let names: Vec<_> = systems.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["Arcadia", "Corvus", "Empty Space"]);
+    assert_eq!(systems[1].lc_name, "corvus");
+}
+
+#[test]
+fn objects_have_kinds_and_positions() {
+    let systems = fixture();
+    let corvus = &systems[1];
+
+    let names: Vec<_> = corvus.objects.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "Corvus",
+            "Jangala",
+            "Jangala Moon",
+            "Derelict Probe",
+            "Research Station"
+        ]
+    );
+
+    let jangala = object(corvus, "Jangala");
+    assert_eq!(jangala.kind, Kind::Planet);
+    assert_eq!((jangala.pos.x, jangala.pos.y), (-4500.5, 2500.25));
+
+    assert_eq!(jangala.size(), Some(Size::Medium));
+    assert_eq!(object(corvus, "Jangala Moon").size(), Some(Size::Tiny));
+
+    let probe = object(corvus, "Derelict Probe");
+    assert_eq!(probe.size(), None);
+    assert_eq!(probe.kind, Kind::Entity);
+    assert_eq!((probe.pos.x, probe.pos.y), (3000.0, -1000.0));
+
+    assert!(systems[2].objects.is_empty());
+}
+
+#[test]
+fn missions_are_flagged() {
+    let systems = fixture();
+
+    assert!(!systems[0].mission);
+    assert!(systems[1].mission);
+    assert!(object(&systems[1], "Derelict Probe").mission);
+    assert!(!object(&systems[1], "Jangala").mission);
+}
+
+#[test]
+fn mission_details() {
+    let systems = fixture();
+    let probe = object(&systems[1], "Derelict Probe");
+
+    assert_eq!(
+        probe.missions,
+        [
+            Mission {
+                kind: Some("survey".to_string()),
+                faction: Some("tritachyon".to_string()),
+                reward: Some(45000.0),
+            },
+            Mission::default(),
+        ]
+    );
+    assert!(object(&systems[1], "Jangala").missions.is_empty());
+}
+
+#[test]
+fn missions_are_counted_by_system() {
+    let systems = fixture();
+    assert_eq!(systems[1].missions().count(), 2);
+    assert_eq!(systems[1].mission_factions(), ["tritachyon"]);
+    assert_eq!(systems[0].missions().count(), 0);
+    assert!(systems[0].mission_factions().is_empty());
+}
+
+#[test]
+fn orbits_and_discovery() {
+    let systems = fixture();
+    let corvus = &systems[1];
+    let parent = |name| {
+        object(corvus, name)
+            .parent
+            .map(|i| corvus.objects[i].name.as_str())
+    };
+
+    assert_eq!(parent("Corvus"), None);
+    assert_eq!(parent("Jangala"), Some("Corvus"));
+    assert_eq!(parent("Jangala Moon"), Some("Jangala"));
+    assert_eq!(parent("Derelict Probe"), Some("Corvus"));
+
+    assert!(object(corvus, "Jangala").discovered);
+    assert!(!object(corvus, "Research Station").discovered);
+}
+
+#[test]
+fn markets_report_deficits() {
+    let systems = fixture();
+    let corvus = &systems[1];
+
+    let market = object(corvus, "Jangala").market.as_ref().unwrap();
+    assert_eq!(market.commodities.len(), 3);
+
+    let deficits: Vec<_> = market
+        .deficits()
+        .map(|c| (c.id.as_str(), c.deficit()))
+        .collect();
+    assert_eq!(deficits, [("heavy_machinery", 3.0)]);
+
+    assert!(object(corvus, "Jangala Moon").market.is_none());
+}
+
+#[test]
+fn resources_and_locations() {
+    let systems = fixture();
+    let (arcadia, corvus) = (&systems[0], &systems[1]);
+
+    assert_eq!(arcadia.resource(Resource::Ore), Some(3));
+    assert_eq!(arcadia.resource(Resource::RareOre), Some(1));
+    assert_eq!(arcadia.resource(Resource::Farmland), None);
+    assert_eq!(corvus.resource(Resource::Farmland), Some(3));
+    assert_eq!(Resource::Farmland.grade_name(3), "rich");
+
+    let distance = arcadia
+        .location
+        .unwrap()
+        .distance(&corvus.location.unwrap());
+    assert_eq!(distance, 10000.0);
+    assert!(systems[2].location.is_none());
+}
+
+#[test]
+fn factions_and_relationships() {
+    let sector = load_sector(Path::new(FIXTURE)).unwrap();
+    assert_eq!(sector.relationships.get("hegemony"), Some(&0.1));
+    assert_eq!(sector.relationships.get("pirates"), Some(&-0.65));
+
+    let corvus = &sector.systems[1];
+    let jangala = object(corvus, "Jangala");
+    assert_eq!(jangala.faction.as_deref(), Some("hegemony"));
+    assert_eq!(jangala.standing(), Some(Standing::Neutral));
+
+    let probe = object(corvus, "Derelict Probe");
+    assert_eq!(probe.faction.as_deref(), Some("pirates"));
+    assert_eq!(probe.standing(), Some(Standing::Hostile));
+
+    assert_eq!(object(corvus, "Corvus").standing(), None);
+    assert_eq!(corvus.controlling_faction(), Some("hegemony"));
+    assert_eq!(sector.systems[0].controlling_faction(), None);
+    assert_eq!(Standing::from_relationship(0.5), Standing::Friendly);
+}
+
+#[test]
+fn home_world_candidates_are_habitable_and_terran_like() {
+    let systems = fixture();
+    let corvus = &systems[1];
+
+    let jangala = object(corvus, "Jangala");
+    assert!(jangala.terran_like());
+    assert_eq!(jangala.market.as_ref().unwrap().hazard(), 0.75);
+    assert!(!object(&systems[0], "Agreus").terran_like());
+    assert_eq!(jangala.atmosphere, Some(Atmosphere::Breathable));
+    assert_eq!(object(&systems[0], "Agreus").atmosphere, None);
+    assert_eq!(jangala.temperature, Some(Temperature::Temperate));
+    assert_eq!(object(corvus, "Jangala Moon").temperature, None);
+    assert_eq!(
+        Temperature::from_conditions(["ore_sparse", "very_hot"]),
+        Temperature::VeryHot
+    );
+    assert_eq!(
+        Atmosphere::from_conditions(["habitable", "thin_atmosphere"]),
+        Some(Atmosphere::Thin)
+    );
+
+    let candidates = home_world_candidates(&systems);
+    assert_eq!(candidates.len(), 1);
+    let (system, object, hazard) = candidates[0];
+    assert_eq!(systems[system].objects[object].name, "Jangala");
+    assert_eq!(hazard, 0.75);
+}
+
+#[test]
+fn terrain_is_parsed() {
+    let systems = fixture();
+    let terrain = &systems[1].terrain;
+    assert_eq!(terrain.len(), 2);
+
+    let belt = terrain[0];
+    assert_eq!(belt.kind, TerrainKind::AsteroidBelt);
+    assert_eq!((belt.inner_radius, belt.outer_radius), (5750.0, 6250.0));
+
+    let nebula = terrain[1];
+    assert_eq!(nebula.kind, TerrainKind::Nebula);
+    assert_eq!((nebula.center.x, nebula.center.y), (-8000.0, 4000.0));
+    assert_eq!((nebula.inner_radius, nebula.outer_radius), (0.0, 2500.0));
+
+    assert!(systems[0].terrain.is_empty());
+    assert!(systems[1].has_asteroid_belt());
+    assert!(!systems[0].has_asteroid_belt());
+}
+
+#[test]
+fn iterating_objects() {
+    let systems = fixture();
+
+    let total: usize = systems.iter().map(|s| s.objects.len()).sum();
+    assert_eq!(all_objects(&systems).count(), total);
+
+    let (system, object) = all_objects(&systems)
+        .find(|(_, o)| o.name == "Jangala Moon")
+        .unwrap();
+    assert_eq!(system.name, "Corvus");
+
+    let (index, moon) = system
+        .objects()
+        .find(|(_, o)| o.name == "Jangala Moon")
+        .unwrap();
+    assert_eq!(moon.name, object.name);
+    assert_eq!(system.objects[index].name, "Jangala Moon");
+}
+
+#[test]
+fn unique_structures_are_found() {
+    let systems = fixture();
+
+    let shunt = object(&systems[0], "Coronal Hypershunt");
+    assert_eq!(shunt.kind, Kind::Entity);
+    assert_eq!(shunt.entity_type.as_deref(), Some("coronal_tap"));
+    assert_eq!(shunt.planet_type, None);
+    assert_eq!(shunt.unique(), Some(Unique::CoronalHypershunt));
+    assert_eq!(shunt.category(), Category::Entity);
+    assert_eq!(object(&systems[0], "Agreus").category(), Category::Planet);
+
+    let uniques = unique_structures(&systems);
+    assert_eq!(uniques.len(), 1);
+    let (system, object, unique) = uniques[0];
+    assert_eq!(systems[system].objects[object].name, "Coronal Hypershunt");
+    assert_eq!(unique, Unique::CoronalHypershunt);
+}
+
+#[test]
+fn fleets_are_counted() {
+    let systems = fixture();
+
+    assert_eq!(systems[0].fleets.len(), 2);
+    assert_eq!(systems[0].remnant_fleets(), 1);
+    assert!(systems[1].fleets.is_empty());
+}
+
+#[test]
+fn parse_save_matches_load_save() {
+    let xml = std::fs::read_to_string(FIXTURE).unwrap();
+    let parsed = parse_save(&xml).unwrap();
+
+    assert_eq!(format!("{parsed:?}"), format!("{:?}", fixture()));
+}
+
+#[test]
+fn gzipped_saves_are_decompressed() {
+    use std::io::Write;
+
+    let path = std::env::temp_dir().join(format!("scansector-{}.xml.gz", std::process::id()));
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(&std::fs::read(FIXTURE).unwrap()).unwrap();
+    std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+    let loaded = load_save(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(format!("{:?}", loaded.unwrap()), format!("{:?}", fixture()));
+}
+
+#[test]
+fn malformed_xml_is_an_error() {
+    assert!(parse_save("<Sstm bN=\"Broken\"><Plnt></Sstm>").is_err());
+}
+
+#[test]
+fn corrupt_systems_are_skipped() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/corrupt.xml");
+    let sector = load_sector(Path::new(path)).unwrap();
+
+    // Arcadia is cut off by the end of the file before its system closes
+    let names: Vec<_> = sector.systems.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["Corvus"]);
+    assert_eq!(
+        sector.problems,
+        [
+            "Skipped a system: unknown entity reference 'bogus' at line 16, column 28",
+            "The rest of the save was skipped. Failed to read save: the save ends inside the \
+             element at line 20",
+        ]
+    );
+}
+
+#[test]
+fn systems_and_factions_are_found_within_each_other() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/nested.xml");
+    let sector = load_sector(Path::new(path)).unwrap();
+
+    // Eventide is first serialized as a faction's home, and the pirates by a planet
+    let names: Vec<_> = sector.systems.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["Corvus", "Eventide"]);
+    assert_eq!(object(&sector.systems[0], "Corvus").kind, Kind::Planet);
+    assert_eq!(sector.relationships.get("hegemony"), Some(&0.1));
+    assert_eq!(sector.relationships.get("pirates"), Some(&-0.65));
+    assert!(sector.problems.is_empty(), "{:?}", sector.problems);
+}
+
+#[test]
+fn hyperspace_storms_are_read() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/storms.xml");
+    let sector = load_sector(Path::new(path)).unwrap();
+
+    // Only the raging and gathering cells of the four by two grid around the origin count
+    let storms: Vec<_> = sector
+        .storms
+        .iter()
+        .map(|s| (s.center.x, s.center.y, s.raging))
+        .collect();
+    assert_eq!(storms, [(-300.0, -100.0, true), (300.0, 100.0, false)]);
+    assert_eq!(sector.storms[0].size, HYPERSPACE_TILE_SIZE);
+    let distance = |name: &str| {
+        let system = sector.systems.iter().find(|s| s.name == name).unwrap();
+        system.storm_distance(&sector.storms)
+    };
+    assert_eq!(distance("Corvus"), Some(0.0));
+    assert_eq!(distance("Askonia"), Some(900.0));
+    assert!(sector.problems.is_empty(), "{:?}", sector.problems);
+
+    // Hyperspace first met through a system is found all the same, around wherever it sits
+    let xml = r#"<sector><Sstm bN="Corvus"><o><Plnt><loc>0|0</loc><j0>{"f0":"Corvus"}</j0>
+        <dest><CCEnt><loc>1000|0</loc><plugin cl="HyperspaceTerrainPlugin">
+        <params><w>2</w><h>2</h></params><savedActiveCells><c><i>1</i><j>1</j>
+        <state>STORM_WANE</state></c></savedActiveCells></plugin></CCEnt></dest>
+        </Plnt></o></Sstm></sector>"#;
+    let sector = read_sector(xml.as_bytes()).unwrap();
+    let storm = sector.storms.first().expect("the storm should be read");
+    assert_eq!(
+        (storm.center.x, storm.center.y, storm.raging),
+        (1100.0, 100.0, false)
+    );
+}
+
+#[test]
+fn stars_are_classified() {
+    let systems = fixture();
+    let system = |name: &str| systems.iter().find(|s| s.name == name).unwrap();
+
+    let (star, class) = system("Corvus").star().expect("Corvus has a star");
+    assert_eq!(star.name, "Corvus");
+    assert_eq!(class, StarClass::Yellow);
+    assert_eq!(system("Corvus").solar_power(), Some(SolarPower::Adequate));
+    assert_eq!(system("Arcadia").solar_power(), Some(SolarPower::Poor));
+    assert!(!SolarPower::Poor.viable());
+    assert_eq!(system("Empty Space").star().map(|s| s.1), None);
+    assert_eq!(object(system("Corvus"), "Jangala").star_class(), None);
+}
+
+#[test]
+fn salvage_is_listed() {
+    let systems = fixture();
+    let corvus = &systems[1];
+
+    let station = object(corvus, "Research Station");
+    assert_eq!(station.salvage, ["blueprints_low", "weapons2"]);
+    assert!(station.has_blueprints());
+    assert!(!object(corvus, "Derelict Probe").has_blueprints());
+}
+
+#[test]
+fn headline_resources() {
+    let systems = fixture();
+    let system = |name: &str| systems.iter().find(|s| s.name == name).unwrap();
+
+    assert_eq!(
+        system("Corvus").headline_resource(),
+        Some((Resource::Farmland, 3))
+    );
+    assert_eq!(
+        system("Arcadia").headline_resource(),
+        Some((Resource::Ore, 3))
+    );
+    assert_eq!(system("Empty Space").headline_resource(), None);
+
+    let corvus = system("Corvus").headline_resources();
+    assert_eq!(corvus.first(), Some(&(Resource::Farmland, 3)));
+    assert!(corvus.contains(&(Resource::Organics, 2)));
+    assert!(system("Empty Space").headline_resources().is_empty());
+}
+
+#[test]
+fn ports_are_found() {
+    let systems = fixture();
+    let system = |name: &str| systems.iter().find(|s| s.name == name).unwrap();
+
+    let jangala = object(system("Corvus"), "Jangala").market.as_ref().unwrap();
+    assert_eq!(
+        jangala.industries,
+        ["population", "waystation", "spaceport"]
+    );
+    assert_eq!(jangala.port(), Some(Port::Spaceport));
+    assert_eq!(jangala.size, Some(6));
+    assert_eq!(object(system("Arcadia"), "Agreus").market_size(), None);
+    assert_eq!(system("Corvus").port(), Some(Port::Spaceport));
+    assert_eq!(system("Arcadia").port(), None);
+}
+
+#[test]
+fn trade_links_join_colonies_to_nearest_markets() {
+    let colony = |system: &str, loc: &str, faction: &str| {
+        format!(
+            r#"<Sstm bN="{system}"><loc>{loc}</loc><o><Plnt><loc>0|0</loc>
+            <j0>{{"f0":"{system} I"}}</j0><faction>{faction}</faction><Mrkt/></Plnt></o></Sstm>"#
+        )
+    };
+    let xml = [
+        colony("Home", "0|0", "player"),
+        colony("Near", "1000|0", "hegemony"),
+        colony("Far", "9000|0", "tritachyon"),
+        r#"<Sstm bN="Quiet"><loc>10|0</loc></Sstm>"#.to_string(),
+    ]
+    .concat();
+    let systems = parse_save(&format!("<systems>{xml}</systems>")).unwrap();
+    let index = |name: &str| systems.iter().position(|s| s.name == name).unwrap();
+
+    assert_eq!(trade_links(&systems, 1), [(index("Home"), index("Near"))]);
+    assert_eq!(
+        trade_links(&systems, 5),
+        [
+            (index("Home"), index("Near")),
+            (index("Home"), index("Far"))
+        ]
+    );
+}
+
+#[test]
+fn jump_points_are_inner_or_fringe() {
+    let jump_point = |name: &str, loc: &str| {
+        format!(
+            r#"<CCEnt><loc>{loc}</loc><type>jump_point</type><j0>{{"f0":"{name}"}}</j0></CCEnt>"#
+        )
+    };
+    let system = |name: &str, objects: &[String]| {
+        format!(r#"<Sstm bN="{name}"><o>{}</o></Sstm>"#, objects.concat())
+    };
+    let xml = [
+        system(
+            "Named",
+            &[
+                jump_point("Named Fringe Jump-point", "100|0"),
+                jump_point("Inner System Jump-point", "9000|0"),
+            ],
+        ),
+        system(
+            "Unnamed",
+            &[jump_point("Near", "1000|0"), jump_point("Far", "0|8000")],
+        ),
+        system("Single", &[jump_point("Only", "9000|0")]),
+    ]
+    .concat();
+    let systems = parse_save(&format!("<systems>{xml}</systems>")).unwrap();
+    let kinds = |name: &str| {
+        let system = systems.iter().find(|s| s.name == name).unwrap();
+        (0..system.objects.len())
+            .map(|i| system.jump_point(i))
+            .collect::<Vec<_>>()
+    };
+
+    use JumpPointKind::{Fringe, Inner};
+    assert_eq!(kinds("Named"), [Some(Fringe), Some(Inner)]);
+    assert_eq!(kinds("Unnamed"), [Some(Inner), Some(Fringe)]);
+    assert_eq!(kinds("Single"), [Some(Inner)]);
+}
+
+#[test]
+fn faction_display_names() {
+    assert_eq!(faction_name("tritachyon"), "Tri-Tachyon");
+    assert_eq!(faction_name("persean"), "Persean League");
+    assert_eq!(faction_name("some_mod_faction"), "some_mod_faction");
+}
+
+#[test]
+fn the_player_is_found() {
+    let systems = parse_save(
+        r#"<systems>
+            <Sstm bN="Askonia"><o><CFleet><faction>sindrian_diktat</faction></CFleet></o></Sstm>
+            <Sstm bN="Corvus"><o>
+                <playerFleet cl="CFleet"><faction>player</faction></playerFleet>
+            </o></Sstm>
+        </systems>"#,
+    )
+    .unwrap();
+    assert!(!systems[0].has_player());
+    assert!(systems[1].has_player());
+    assert!(!fixture().iter().any(System::has_player));
+}
+
+#[test]
+fn rings_and_tidal_locking() {
+    let systems = parse_save(
+        r#"<systems><Sstm bN="Ringed"><o>
+            <Plnt z="1"><loc>0|0</loc><type>star_yellow</type>
+                <spec><rotation>0</rotation></spec><j0>{"f0":"Sun"}</j0></Plnt>
+            <Plnt z="2"><loc>5000|0</loc><type>gas_giant</type><orbit><f ref="1"/></orbit>
+                <spec><rotation>3.5</rotation></spec><j0>{"f0":"Giant"}</j0></Plnt>
+            <Plnt z="3"><loc>5400|0</loc><type>barren</type><orbit><f ref="2"/></orbit>
+                <spec><rotation>0.0</rotation></spec><j0>{"f0":"Moon"}</j0></Plnt>
+            <RingBand><orbit><f ref="2"/></orbit>
+                <middleRadius>600</middleRadius><bandWidthInEngine>200</bandWidthInEngine></RingBand>
+            <RingBand><orbit><f ref="2"/></orbit><middleRadius>900</middleRadius></RingBand>
+        </o></Sstm></systems>"#,
+    )
+    .unwrap();
+    let system = &systems[0];
+
+    let sun = object(system, "Sun");
+    assert!(!sun.tidally_locked);
+    assert!(sun.rings.is_empty());
+
+    let giant = object(system, "Giant");
+    assert!(!giant.tidally_locked);
+    let rings: Vec<_> = giant
+        .rings
+        .iter()
+        .map(|r| (r.center.x, r.inner_radius, r.outer_radius))
+        .collect();
+    assert_eq!(rings, [(5000.0, 500.0, 700.0), (5000.0, 900.0, 900.0)]);
+
+    assert!(object(system, "Moon").tidally_locked);
+}
+
+#[test]
+fn uncolonized_systems() {
+    let systems = parse_save(
+        r#"<systems>
+            <Sstm bN="Claimed"><o>
+                <Plnt><loc>0|0</loc><j0>{"f0":"Star"}</j0></Plnt>
+                <CCEnt><loc>10|0</loc><faction>pirates</faction><j0>{"f0":"Base"}</j0></CCEnt>
+            </o></Sstm>
+            <Sstm bN="Pristine"><o>
+                <Plnt><loc>0|0</loc><j0>{"f0":"Star"}</j0></Plnt>
+                <CCEnt><loc>10|0</loc><j0>{"f0":"Probe"}</j0></CCEnt>
+            </o></Sstm>
+        </systems>"#,
+    )
+    .unwrap();
+    assert!(!systems[0].uncolonized());
+    assert!(systems[1].uncolonized());
+
+    // Markets rule out the fixture's systems, and there's nothing to settle in empty space
+    assert!(!fixture().iter().any(System::uncolonized));
+}
+
+#[test]
+fn sectors_are_read_from_streams() {
+    use std::io::Write;
+
+    let plain = std::fs::read(FIXTURE).unwrap();
+    let expected = format!("{:?}", fixture());
+    let sector = read_sector(plain.as_slice()).unwrap();
+    assert_eq!(format!("{:?}", sector.systems), expected);
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+    encoder.write_all(&plain).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let sector = read_sector(compressed.as_slice()).unwrap();
+    assert_eq!(format!("{:?}", sector.systems), expected);
+}
+
+#[test]
+fn unvisited_systems_are_nearest_first() {
+    let systems = fixture();
+    assert!(systems[1].entered);
+    assert!(!systems[0].visited());
+    // Arcadia, then Empty Space, which has no position to measure from
+    assert_eq!(
+        unvisited_systems(&systems)
+            .iter()
+            .map(|&(i, _)| i)
+            .collect::<Vec<_>>(),
+        [0, 2]
+    );
+
+    let systems = parse_save(
+        r#"<systems>
+            <Sstm bN="Far"><loc>0|9000</loc></Sstm>
+            <Sstm bN="Here"><loc>0|0</loc><o><playerFleet cl="CFleet"/></o></Sstm>
+            <Sstm bN="Near"><loc>3000|4000</loc></Sstm>
+        </systems>"#,
+    )
+    .unwrap();
+    assert_eq!(
+        unvisited_systems(&systems),
+        [(2, Some(5000.0)), (0, Some(9000.0))]
+    );
+}
+
+#[test]
+fn remnant_danger_and_stations() {
+    let systems = parse_save(
+        r#"<systems>
+            <Sstm bN="Infested"><o>
+                <CCEnt><loc>0|0</loc><type>warning_beacon</type>
+                    <tags><st>beacon</st><st>beacon_high</st></tags>
+                    <j0>{"f0":"Warning Beacon"}</j0></CCEnt>
+                <CCEnt><loc>0|0</loc><type>warning_beacon</type>
+                    <tags><st>beacon_low</st></tags><j0>{"f0":"Warning Beacon"}</j0></CCEnt>
+                <CFleet><faction>remnant</faction><stationMode>true</stationMode></CFleet>
+                <CFleet><faction>remnant</faction></CFleet>
+            </o></Sstm>
+            <Sstm bN="Tagged"><o>
+                <CCEnt><loc>0|0</loc><type>derelict_probe</type>
+                    <tags><st>beacon_high</st></tags><j0>{"f0":"Probe"}</j0></CCEnt>
+            </o></Sstm>
+        </systems>"#,
+    )
+    .unwrap();
+    assert_eq!(systems[0].danger(), Some(Danger::High));
+    assert_eq!(systems[0].remnant_stations(), 1);
+    assert_eq!(systems[0].remnant_fleets(), 2);
+    // Only beacons warn of danger
+    assert_eq!(systems[1].danger(), None);
+    assert!(Danger::Low < Danger::Medium);
+
+    assert!(fixture().iter().all(|s| s.danger().is_none()));
+}
+
+#[test]
+fn infrastructure_and_whose_it_is() {
+    let entity = |kind: &str, faction: &str| {
+        format!(
+            r#"<CCEnt><loc>0|0</loc><type>{kind}</type><faction>{faction}</faction>
+                <j0>{{"f0":"{kind}"}}</j0></CCEnt>"#
+        )
+    };
+    let colony = r#"<Plnt><loc>9|9</loc><faction>player</faction><j0>{"f0":"Home"}</j0>
+        <Mrkt><size>3</size></Mrkt></Plnt>"#;
+    let systems = parse_save(&format!(
+        r#"<systems>
+            <Sstm bN="Mine"><o>{colony}{}{}</o></Sstm>
+            <Sstm bN="Theirs"><o>{}</o></Sstm>
+        </systems>"#,
+        entity("comm_relay_makeshift", "player"),
+        entity("nav_buoy", "hegemony"),
+        entity("sensor_array", "tritachyon"),
+    ))
+    .unwrap();
+
+    let mine = &systems[0];
+    assert_eq!(
+        mine.objects[1].infrastructure(),
+        Some(Infrastructure::CommRelay)
+    );
+    assert_eq!(
+        mine.missing_infrastructure(),
+        [Infrastructure::NavBuoy, Infrastructure::SensorArray]
+    );
+    // Without a colony of the player's, nothing there needs it
+    assert!(systems[1].missing_infrastructure().is_empty());
+    assert_eq!(
+        Infrastructure::from_entity_type("sensor_array"),
+        Some(Infrastructure::SensorArray)
+    );
+
+    assert!(!mine.objects[1].domain_era());
+    assert!(mine.objects[2].domain_era());
+    let arrays: Vec<_> = systems[1].domain_sensor_arrays().map(|o| &o.name).collect();
+    assert_eq!(arrays, ["sensor_array"]);
+}
+
+#[test]
+fn unclaimed_sensor_arrays_are_inactive() {
+    let systems = parse_save(
+        r#"<systems><Sstm bN="Quiet"><o>
+            <CCEnt><loc>0|0</loc><type>sensor_array</type><faction>neutral</faction>
+                <j0>{"f0":"Old Array"}</j0></CCEnt>
+            <CCEnt><loc>9|0</loc><type>sensor_array_makeshift</type><faction>pirates</faction>
+                <j0>{"f0":"New Array"}</j0></CCEnt>
+        </o></Sstm></systems>"#,
+    )
+    .unwrap();
+    let system = &systems[0];
+    assert!(!object(system, "Old Array").operational());
+    assert!(object(system, "New Array").operational());
+    assert_eq!(system.domain_sensor_arrays().count(), 0);
+}
+
+#[test]
+fn mission_types() {
+    let systems = fixture();
+    let probe = systems
+        .iter()
+        .flat_map(|s| &s.objects)
+        .find(|o| !o.missions.is_empty())
+        .unwrap();
+    assert_eq!(probe.mission_type(), Some(MissionType::Survey));
+    assert_eq!(MissionType::from_kind("PersonBounty"), MissionType::Bounty);
+    assert_eq!(
+        MissionType::from_kind("deliver_cargo"),
+        MissionType::Delivery
+    );
+    assert_eq!(MissionType::from_kind("smuggling"), MissionType::Other);
+}
+
+#[test]
+fn raw_data_is_kept() {
+    let systems = parse_save(
+        r#"<Sstm bN="Raw"><o><Plnt z="7"><loc>0|0</loc><j0>{"f0":"Rock","f1":2}</j0></Plnt></o></Sstm>"#,
+    )
+    .unwrap();
+    let raw = &systems[0].objects[0].raw;
+    assert_eq!(raw.attributes, [("z".to_string(), "7".to_string())]);
+    assert_eq!(raw.j0.as_deref(), Some(r#"{"f0":"Rock","f1":2}"#));
+    assert_eq!(
+        raw.pretty_j0().unwrap(),
+        "{\n  \"f0\": \"Rock\",\n  \"f1\": 2\n}"
+    );
+}
+
+#[test]
+fn unreadable_details_keep_the_object() {
+    let sector = read_sector(
+        r#"<Sstm bN="Drift"><o>
+            <Plnt><loc>0|0</loc><j0>{"f0":"Half Written",</j0></Plnt>
+            <Plnt><loc>1|0</loc><name>Listed</name><j0>[]</j0></Plnt>
+            <CCEnt><loc>2|0</loc><type>derelict_probe</type><j0>?</j0></CCEnt>
+            <Plnt><loc>3|0</loc><j0>{"f0":"Fine"}</j0></Plnt>
+            <Plnt><loc>4|0</loc><name>Unwritten</name></Plnt>
+        </o></Sstm>"#
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let objects = &sector.systems[0].objects;
+    let names: Vec<&str> = objects.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "Half Written",
+            "Listed",
+            "Fine",
+            "Unwritten",
+            "derelict_probe"
+        ]
+    );
+    assert!(objects[0].raw.unreadable);
+    assert!(!objects[2].raw.unreadable);
+    assert!(objects[3].raw.unreadable);
+    assert_eq!(
+        sector.problems,
+        ["Guessed the names of 4 objects in Drift, whose details couldn't be read"]
+    );
+}
+
+#[test]
+fn internal_ids_are_parsed() {
+    let systems = parse_save(
+        r#"<Sstm bN="Corvus"><id>corvus</id><o>
+            <Plnt><loc>0|0</loc><id>corvus_star</id><j0>{"f0":"Corvus"}</j0>
+                <Mrkt><id>market_id</id></Mrkt></Plnt>
+            <Plnt><loc>0|0</loc><j0>{"f0":"Nameless"}</j0><Mrkt><id>market_id</id></Mrkt></Plnt>
+        </o></Sstm>"#,
+    )
+    .unwrap();
+    assert_eq!(systems[0].internal_id.as_deref(), Some("corvus"));
+    assert_eq!(
+        systems[0].objects[0].internal_id.as_deref(),
+        Some("corvus_star")
+    );
+    // The market's own id isn't the planet's
+    assert_eq!(systems[0].objects[1].internal_id, None);
+}
+
+#[test]
+fn same_named_objects_have_their_own_keys() {
+    let systems = parse_save(
+        r#"<Sstm bN="Corvus"><o>
+            <CCEnt><loc>0|0</loc><j0>{"f0":"Stable Location"}</j0></CCEnt>
+            <CCEnt><loc>1|0</loc><id>relay</id><j0>{"f0":"Stable Location"}</j0></CCEnt>
+            <CCEnt><loc>2|0</loc><j0>{"f0":"Stable Location"}</j0></CCEnt>
+        </o></Sstm>"#,
+    )
+    .unwrap();
+    let corvus = &systems[0];
+    let keys: Vec<_> = (0..3).map(|index| corvus.object_key(index)).collect();
+    assert_eq!(
+        keys,
+        [("Stable Location", 0), ("relay", 0), ("Stable Location", 1)]
+    );
+    for (index, (key, nth)) in keys.into_iter().enumerate() {
+        assert_eq!(corvus.find_object(key, nth), Some(index));
+    }
+    assert_eq!(corvus.find_object("Stable Location", 2), None);
+}
+
+#[test]
+fn industries_are_named() {
+    let systems = fixture();
+    let market = object(&systems[1], "Jangala").market.as_ref().unwrap();
+    assert!(market.has_industry("waystation"));
+    assert!(!market.has_industry("fuelprod"));
+
+    assert_eq!(industry_name("fuelprod"), "Fuel Production");
+    assert_eq!(industry_name("heavybatteries"), "Heavy Batteries");
+    assert_eq!(industry_name("modded_industry"), "modded_industry");
+}
+
+#[test]
+fn stable_locations_are_counted() {
+    let entity = |kind: &str| {
+        format!(r#"<CCEnt><loc>0|0</loc><type>{kind}</type><j0>{{"f0":"{kind}"}}</j0></CCEnt>"#)
+    };
+    let systems = parse_save(&format!(
+        r#"<systems><Sstm bN="Built"><o>{}{}{}{}</o></Sstm></systems>"#,
+        entity("stable_location"),
+        entity("comm_relay"),
+        entity("nav_buoy_makeshift"),
+        entity("inactive_gate"),
+    ))
+    .unwrap();
+    assert_eq!(systems[0].stable_locations(), 3);
+    assert_eq!(systems[0].free_stable_locations(), 1);
+    assert!(systems[0].objects[1].occupies_stable_location());
+
+    assert!(fixture().iter().all(|s| s.stable_locations() == 0));
+}
+
+#[test]
+fn black_holes_and_pulsars_are_hazards() {
+    let star = |kind: &str| {
+        format!(r#"<Plnt><loc>0|0</loc><type>{kind}</type><j0>{{"f0":"{kind}"}}</j0></Plnt>"#)
+    };
+    let systems = parse_save(&format!(
+        r#"<systems><Sstm bN="Deep"><o>{}{}{}</o></Sstm></systems>"#,
+        star("star_neutron"),
+        star("black_hole"),
+        star("star_neutron"),
+    ))
+    .unwrap();
+    assert_eq!(
+        systems[0].stellar_hazards(),
+        [StellarHazard::BlackHole, StellarHazard::Pulsar]
+    );
+    assert_eq!(StarClass::Yellow.hazard(), None);
+    assert!(fixture().iter().all(|s| s.stellar_hazards().is_empty()));
+}
+
+#[test]
+fn stars_are_counted() {
+    let star = |name: &str, kind: &str| {
+        format!(r#"<Plnt><loc>0|0</loc><type>{kind}</type><j0>{{"f0":"{name}"}}</j0></Plnt>"#)
+    };
+    let systems = parse_save(&format!(
+        r#"<systems><Sstm bN="Pair"><o>{}{}<Plnt><loc>9|9</loc><type>barren</type>
+            <j0>{{"f0":"Rock"}}</j0></Plnt></o></Sstm></systems>"#,
+        star("A", "star_orange"),
+        star("B", "black_hole"),
+    ))
+    .unwrap();
+    assert_eq!(systems[0].star_count(), 2);
+    assert_eq!(systems[0].multiplicity(), Some(Multiplicity::Binary));
+
+    let fixture = fixture();
+    let multiplicities: Vec<_> = fixture.iter().map(System::multiplicity).collect();
+    assert_eq!(
+        multiplicities,
+        [Some(Multiplicity::Single), Some(Multiplicity::Single), None]
+    );
+    assert_eq!(Multiplicity::from_count(5), Some(Multiplicity::Trinary));
+}
+
+#[test]
+fn colony_defenses() {
+    let systems = fixture();
+    let system = |name: &str| systems.iter().find(|s| s.name == name).unwrap();
+    let jangala = object(system("Corvus"), "Jangala").market.as_ref().unwrap();
+    assert_eq!(jangala.defense(), Some(Defense::Undefended));
+    // Without its structures, there's no telling how well defended a colony is
+    let agreus = object(system("Arcadia"), "Agreus").market.as_ref().unwrap();
+    assert_eq!(agreus.defense(), None);
+
+    let systems = parse_save(
+        r#"<systems><Sstm bN="Fortress"><o><Plnt><loc>0|0</loc><j0>{"f0":"Bastion"}</j0>
+            <Mrkt><industries>
+                <Ind><id>population</id></Ind>
+                <Ind><id>heavybatteries</id></Ind>
+                <Ind><id>starfortress_high</id></Ind>
+            </industries></Mrkt>
+        </Plnt></o></Sstm></systems>"#,
+    )
+    .unwrap();
+    let bastion = systems[0].objects[0].market.as_ref().unwrap();
+    let defenses: Vec<_> = bastion.defenses().collect();
+    assert_eq!(defenses, [("Heavy Batteries", 2), ("Star Fortress", 3)]);
+    assert_eq!(bastion.defense(), Some(Defense::Strong));
+    assert!(!Defense::Strong.weak());
+}
+
+#[test]
+fn survey_levels() {
+    let systems = fixture();
+    let system = |name: &str| systems.iter().find(|s| s.name == name).unwrap();
+    assert_eq!(
+        object(system("Corvus"), "Jangala").survey(),
+        Some(SurveyLevel::Full)
+    );
+    assert_eq!(
+        object(system("Arcadia"), "Agreus").survey(),
+        Some(SurveyLevel::Preliminary)
+    );
+    assert_eq!(object(system("Corvus"), "Corvus").survey(), None);
+    assert_eq!(survey_progress(&systems), (1, 2));
+}
+
+#[test]
+fn anomalous_systems() {
+    let systems = fixture();
+    let found = anomalies(&systems);
+    assert_eq!(found[0], [Anomaly::Unique(Unique::CoronalHypershunt)]);
+    assert!(found[1].is_empty());
+    assert!(found[2].is_empty());
+
+    let system = |name: &str, objects: usize| {
+        let objects: String = (0..objects)
+            .map(|i| format!(r#"<CCEnt><loc>{i}|0</loc><j0>{{"f0":"Buoy {i}"}}</j0></CCEnt>"#))
+            .collect();
+        format!(r#"<Sstm bN="{name}"><o>{objects}</o></Sstm>"#)
+    };
+    let xml: String = (0..10)
+        .map(|i| system(&format!("Plain {i}"), 3))
+        .chain([system("Zenith", 40)])
+        .collect();
+    let systems = parse_save(&format!("<systems>{xml}</systems>")).unwrap();
+    let found = anomalies(&systems);
+    assert!(found[..10].iter().all(Vec::is_empty));
+    assert_eq!(found[10], [Anomaly::Crowded(40)]);
+}
+
+#[test]
+fn sustained_and_one_time_resources() {
+    let systems = parse_save(
+        r#"<systems><Sstm bN="Cache"><o>
+          <CCEnt><loc>0|0</loc><type>volatiles_cache</type><j0>{"f0":"Cache"}</j0>
+            <dropRandom><DropData><group>rare_ore_haul</group></DropData></dropRandom>
+          </CCEnt>
+        </o></Sstm></systems>"#,
+    )
+    .unwrap();
+    let cache = &systems[0].objects[0];
+    assert_eq!(
+        cache.one_time_resources().collect::<Vec<_>>(),
+        [Resource::RareOre, Resource::Volatiles]
+    );
+    assert!(systems[0].supplies(Resource::Volatiles, Supply::OneTime));
+    assert!(!systems[0].supplies(Resource::Volatiles, Supply::Sustained));
+    assert!(!systems[0].supplies(Resource::Ore, Supply::OneTime));
+
+    let arcadia = &fixture()[0];
+    assert!(arcadia.supplies(Resource::Ore, Supply::Sustained));
+    assert!(!arcadia.supplies(Resource::Ore, Supply::OneTime));
+}
+
+#[test]
+fn hazard_is_broken_down_by_source() {
+    let systems = parse_save(
+        r#"<systems><Sstm bN="Harsh"><o>
+          <Plnt><loc>0|0</loc><j0>{"f0":"Rock"}</j0><Mrkt><conditions>
+            <MCon><id>hot</id></MCon>
+            <MCon><id>thin_atmosphere</id></MCon>
+            <MCon><id>tectonic_activity</id></MCon>
+            <MCon><id>meteor_impacts</id></MCon>
+            <MCon><id>ore_sparse</id></MCon>
+          </conditions></Mrkt></Plnt>
+        </o></Sstm></systems>"#,
+    )
+    .unwrap();
+    let market = systems[0].objects[0].market.as_ref().unwrap();
+    assert_eq!(market.hazards().count(), 4);
+    assert_eq!(
+        market.hazard_breakdown(),
+        [
+            (HazardSource::Atmosphere, 0.25),
+            (HazardSource::Temperature, 0.25),
+            (HazardSource::Geology, 0.75),
+        ]
+    );
+    assert_eq!(market.hazard(), 2.25);
+
+    let systems = fixture();
+    let jangala = object(&systems[1], "Jangala");
+    assert_eq!(
+        jangala.market.as_ref().unwrap().hazard_breakdown(),
+        [(HazardSource::Atmosphere, -0.25)]
+    );
+}
+
+#[test]
+fn gates_are_active_once_scanned() {
+    let gate = |memory: &str| {
+        format!(
+            r#"<CCEnt><loc>0|0</loc><type>inactive_gate</type><j0>{{"f0":"Gate"}}</j0>{memory}</CCEnt>"#
+        )
+    };
+    let systems = parse_save(&format!(
+        r#"<systems><Sstm bN="Gated"><o>{}{}</o></Sstm></systems>"#,
+        gate("<memory><d><e><st>$gateScanned</st><bo>true</bo></e></d></memory>"),
+        gate(""),
+    ))
+    .unwrap();
+    let objects = &systems[0].objects;
+    assert_eq!(objects[0].gate_state(), Some(GateState::Active));
+    assert_eq!(objects[1].gate_state(), Some(GateState::Inactive));
+    assert_eq!(object(&fixture()[1], "Jangala").gate_state(), None);
+}
+
+#[test]
+fn territory_counts_controlled_systems() {
+    let colony = |faction: &str| {
+        format!(
+            r#"<Plnt><loc>0|0</loc><j0>{{"f0":"World"}}</j0><faction>{faction}</faction><Mrkt/></Plnt>"#
+        )
+    };
+    let systems = parse_save(&format!(
+        r#"<systems>
+          <Sstm bN="A"><o>{}</o></Sstm>
+          <Sstm bN="B"><o>{}</o></Sstm>
+          <Sstm bN="C"><o>{}</o></Sstm>
+          <Sstm bN="D"><o></o></Sstm>
+        </systems>"#,
+        colony("tritachyon"),
+        colony("hegemony"),
+        colony("tritachyon"),
+    ))
+    .unwrap();
+    assert_eq!(territory(&systems), [("tritachyon", 2), ("hegemony", 1)]);
+    assert_eq!(territory(&fixture()), [("hegemony", 1)]);
+}
+
+#[test]
+fn generated_systems_are_marked() {
+    let systems = parse_save(
+        r#"<systems>
+          <Sstm bN="Generated"><procgen>true</procgen><o></o></Sstm>
+          <Sstm bN="Story"><procgen>false</procgen><o></o></Sstm>
+        </systems>"#,
+    )
+    .unwrap();
+    assert!(systems[0].procgen);
+    assert!(!systems[1].procgen);
+    assert!(marks_procgen(&systems));
+    assert!(!marks_procgen(&fixture()));
+}
+
+#[test]
+fn orbital_phase() {
+    let systems = parse_save(
+        r#"<systems><Sstm bN="Orbits"><o>
+            <Plnt z="1"><loc>0|0</loc><type>star_yellow</type><j0>{"f0":"Sun"}</j0></Plnt>
+            <Plnt z="2"><loc>0|2500</loc><type>barren</type>
+                <orbit><f ref="1"/><a>90</a><r>2500</r><p>120</p></orbit>
+                <j0>{"f0":"Rock"}</j0></Plnt>
+            <Plnt z="3"><loc>-300|0</loc><type>barren</type><orbit><f ref="1"/></orbit>
+                <j0>{"f0":"Pebble"}</j0></Plnt>
+        </o></Sstm></systems>"#,
+    )
+    .unwrap();
+    let system = &systems[0];
+
+    let rock = object(system, "Rock").orbit.unwrap();
+    assert_eq!(rock.radius, Some(2500.0));
+    assert_eq!(rock.period, Some(120.0));
+    assert_eq!(rock.angle_after(30.0), Some(180.0));
+    assert_eq!(rock.angle_after(330.0), Some(0.0));
+
+    let index = |name| system.objects.iter().position(|o| o.name == name).unwrap();
+    assert_eq!(system.orbital_angle(index("Rock")), Some(90.0));
+    assert_eq!(system.orbital_angle(index("Pebble")), Some(180.0));
+    assert_eq!(
+        object(system, "Pebble").orbit.unwrap().angle_after(30.0),
+        None
+    );
+    assert_eq!(system.orbital_angle(index("Sun")), None);
+}
+
+#[test]
+fn free_ports() {
+    let systems = parse_save(
+        r#"<systems>
+            <Sstm bN="Flagged"><o><Plnt><loc>0|0</loc><j0>{"f0":"Haven"}</j0>
+                <Mrkt><freePort>true</freePort></Mrkt></Plnt></o></Sstm>
+            <Sstm bN="Conditioned"><o><Plnt><loc>0|0</loc><j0>{"f0":"Den"}</j0>
+                <Mrkt><conditions><MCon><id>free_market</id></MCon></conditions></Mrkt></Plnt>
+            </o></Sstm>
+            <Sstm bN="Lawful"><o><Plnt><loc>0|0</loc><j0>{"f0":"Court"}</j0>
+                <Mrkt><freePort>false</freePort></Mrkt></Plnt></o></Sstm>
+        </systems>"#,
+    )
+    .unwrap();
+    let free: Vec<_> = systems.iter().map(System::has_free_port).collect();
+    assert_eq!(free, [true, true, false]);
+    assert!(!fixture().iter().any(System::has_free_port));
+}
+
+#[test]
+fn changes_between_loads() {
+    let save = |faction: &str, last: &str| {
+        parse_save(&format!(
+            r#"<systems><Sstm bN="Live"><o>
+              <Plnt><loc>0|0</loc><j0>{{"f0":"Sun"}}</j0></Plnt>
+              <Plnt><loc>10|0</loc><faction>{faction}</faction><j0>{{"f0":"Haven"}}</j0></Plnt>
+              <CCEnt><loc>5|5</loc><j0>{{"f0":"{last}"}}</j0></CCEnt>
+            </o></Sstm></systems>"#
+        ))
+        .unwrap()
+    };
+    let before = save("hegemony", "Probe");
+    assert!(changes(&before, &before).is_empty());
+
+    let after = changes(&before, &save("pirates", "Beacon"));
+    let found: Vec<_> = after["Live"]
+        .iter()
+        .map(|c| (c.name.as_str(), c.change))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("Beacon", Change::Appeared),
+            ("Haven", Change::Changed),
+            ("Probe", Change::Disappeared),
+        ]
+    );
+
+    // Moving along an orbit isn't a change
+    let moved = parse_save(
+        r#"<systems><Sstm bN="Live"><o>
+          <Plnt><loc>10|10</loc><j0>{"f0":"Sun"}</j0></Plnt>
+          <Plnt><loc>0|10</loc><faction>hegemony</faction><j0>{"f0":"Haven"}</j0></Plnt>
+          <CCEnt><loc>0|5</loc><j0>{"f0":"Probe"}</j0></CCEnt>
+        </o></Sstm></systems>"#,
+    )
+    .unwrap();
+    assert!(changes(&before, &moved).is_empty());
+}
+
+#[test]
+fn object_kinds_can_be_left_out() {
+    let options = ParseOptions {
+        entities: false,
+        fleets: false,
+        terrain: false,
+    };
+    let plain = std::fs::read(FIXTURE).unwrap();
+    let systems = read_sector_with(plain.as_slice(), options).unwrap().systems;
+    let everything = fixture();
+    assert_eq!(systems.len(), everything.len());
+    for (system, all) in systems.iter().zip(&everything) {
+        assert!(system.objects.iter().all(|o| o.kind == Kind::Planet));
+        let planets = all
+            .objects
+            .iter()
+            .filter(|o| o.kind == Kind::Planet)
+            .count();
+        assert_eq!(system.objects.len(), planets);
+        assert!(system.terrain.is_empty() && system.fleets.is_empty());
+    }
+
+    // Other fleets go, but the player's is kept
+    let systems = read_sector_with(
+        r#"<systems><Sstm bN="Corvus"><o>
+            <CFleet><faction>hegemony</faction></CFleet>
+            <playerFleet cl="CFleet"><faction>player</faction></playerFleet>
+        </o></Sstm></systems>"#
+            .as_bytes(),
+        options,
+    )
+    .unwrap()
+    .systems;
+    assert_eq!(systems[0].fleets.len(), 1);
+    assert!(systems[0].has_player());
+}
+
+#[test]
+fn systems_are_placed_in_regions() {
+    let systems = parse_save(
+        r#"<sector><Sstm z="1" bN="Alpha"><loc>-100|200</loc>
+            <constellation z="2">
+                <systems><Sstm ref="1"/><Sstm z="3" bN="Beta"><loc>50|50</loc>
+                    <constellation ref="2"/><o/></Sstm></systems>
+                <type>NEBULA</type><namePick><spec><name>Hydra</name></spec></namePick>
+            </constellation><o/></Sstm>
+            <Sstm ref="3"/>
+            <Sstm bN="Gamma"><loc>10|-10</loc><o/></Sstm>
+            <Sstm bN="Lost"><o/></Sstm>
+        </sector>"#,
+    )
+    .unwrap();
+    let regions: Vec<_> = systems
+        .iter()
+        .map(|s| (s.name.as_str(), s.region()))
+        .collect();
+    assert_eq!(
+        regions,
+        [
+            ("Alpha", Some("Hydra Nebula")),
+            ("Beta", Some("Hydra Nebula")),
+            ("Gamma", Some("South-East")),
+            ("Lost", None),
+        ]
+    );
+    assert_eq!(systems[0].quadrant(), Some(Quadrant::NorthWest));
+    assert!(fixture().iter().all(|s| s.constellation.is_none()));
+}
+
+#[test]
+fn a_moon_keeps_only_its_own_details() {
+    // The colonised parent is written out in full as the focus of the moon's orbit
+    let systems = parse_save(
+        r#"<systems><Sstm bN="Nested"><o>
+            <Plnt z="1"><loc>400|0</loc><type>barren</type>
+                <orbit><f cl="Plnt" z="2"><loc>0|0</loc><type>terran</type>
+                    <j0>{"f0":"Parent"}</j0><faction>hegemony</faction>
+                    <Mrkt><size>6</size><conditions><MCon><id>hot</id></MCon></conditions></Mrkt>
+                    <mem><MReq><reason>survey</reason></MReq></mem>
+                    <dropRandom><DropData><group>rare_ore_haul</group></DropData></dropRandom>
+                    <memory><d><e><st>$gateScanned</st><bo>true</bo></e></d></memory>
+                </f></orbit>
+                <j0>{"f0":"Moon"}</j0></Plnt>
+        </o></Sstm></systems>"#,
+    )
+    .unwrap();
+    let system = &systems[0];
+
+    let moon = object(system, "Moon");
+    assert!(moon.market.is_none());
+    assert!(moon.temperature.is_none());
+    assert!(moon.missions.is_empty());
+    assert!(moon.salvage.is_empty());
+    assert!(!moon.scanned);
+
+    let parent = object(system, "Parent");
+    assert_eq!(parent.market.as_ref().and_then(|m| m.size), Some(6));
+    assert_eq!(parent.missions.len(), 1);
+    assert_eq!(parent.salvage, ["rare_ore_haul"]);
+    assert!(parent.scanned);
+}
@@ -0,0 +1,66 @@
+use scansector::{parse_number, parse_vector};
+
+fn parse(v: &str) -> Option<(f64, f64)> {
+    parse_vector(v).map(|p| (p.x, p.y))
+}
+
+#[test]
+fn plain() {
+    assert_eq!(parse("10.5|-20"), Some((10.5, -20.0)));
+}
+
+#[test]
+fn whitespace() {
+    assert_eq!(parse(" 10.0 | 20.0 "), Some((10.0, 20.0)));
+    assert_eq!(parse("\n10\t|\t20\n"), Some((10.0, 20.0)));
+}
+
+#[test]
+fn extra_delimiters() {
+    assert_eq!(parse("10|20|"), Some((10.0, 20.0)));
+    assert_eq!(parse("10 | 20 | | "), Some((10.0, 20.0)));
+}
+
+#[test]
+fn missing_or_extra_parts() {
+    assert_eq!(parse("|1|2"), None);
+    assert_eq!(parse("1||2"), None);
+    assert_eq!(parse("1|2|3"), None);
+    assert_eq!(parse("1|2|3|"), None);
+}
+
+#[test]
+fn scientific_notation() {
+    assert_eq!(parse("1.5E3|-2e-1"), Some((1500.0, -0.2)));
+    assert_eq!(parse("1e4|4.20E+2"), Some((10000.0, 420.0)));
+}
+
+#[test]
+fn comma_decimals() {
+    assert_eq!(parse("10,5|-20,25"), Some((10.5, -20.25)));
+    assert_eq!(parse("1,5E3|0"), Some((1500.0, 0.0)));
+    assert_eq!(parse_number("1,234.5"), Some(1234.5));
+    assert_eq!(parse_number(" 3,75 "), Some(3.75));
+    assert_eq!(parse_number("1,5"), Some(1.5));
+}
+
+#[test]
+fn thousands_separators() {
+    assert_eq!(parse_number("1,000"), Some(1000.0));
+    assert_eq!(parse_number("1,000.5"), Some(1000.5));
+    assert_eq!(parse_number("-12,345,678"), Some(-12345678.0));
+    assert_eq!(parse_number("1,000,000"), Some(1_000_000.0));
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(parse(""), None);
+    assert_eq!(parse("10"), None);
+    assert_eq!(parse("10|"), None);
+    assert_eq!(parse("ten|20"), None);
+    assert_eq!(parse("NaN|20"), None);
+    assert_eq!(parse("10|inf"), None);
+    assert_eq!(parse_number("1,2,3"), None);
+    assert_eq!(parse_number("1,5,000"), None);
+    assert_eq!(parse_number("1,000,5"), None);
+}
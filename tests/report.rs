@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use scansector::report::{colony_table, to_markdown};
+use scansector::{load_descriptor, load_sector, Descriptor};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/campaign.xml");
+
+#[test]
+fn report_summarizes_the_save() {
+    let path = Path::new(FIXTURE);
+    let sector = load_sector(path).unwrap();
+    let report = to_markdown(
+        &load_descriptor(path).unwrap(),
+        &sector.systems,
+        &sector.relationships,
+    );
+
+    assert!(report.starts_with("# Test Pilot's Sector\n"));
+    assert!(report.contains("- Saved: 2023-03-14 12:00:00.0 UTC\n"));
+    assert!(report.contains("- Credits: 123457\n"));
+    assert!(report.contains("- Seed: CPAXT-JL4FB-2ZG8D\n"));
+    assert!(report.contains("- Systems: 3\n"));
+    assert!(report.contains("- Factions: 2\n"));
+    assert!(report.contains("- Planets surveyed: 1 of 2\n"));
+    assert!(report.contains("- Jangala in Corvus (75% hazard)\n"));
+    assert!(report.contains("- Ore (abundant): Arcadia\n"));
+    assert!(report.contains("- Farmland (rich): Corvus\n"));
+    assert!(report.contains("## Territory\n\n- Hegemony: 1 system (33%)\n\n"));
+    assert!(report.contains("- Corvus: Derelict Probe\n"));
+}
+
+#[test]
+fn report_without_descriptor() {
+    let sector = load_sector(Path::new(FIXTURE)).unwrap();
+    let report = to_markdown(
+        &Descriptor::default(),
+        &sector.systems,
+        &sector.relationships,
+    );
+
+    assert!(report.starts_with("# Sector Summary\n"));
+    assert!(!report.contains("Saved:"));
+}
+
+#[test]
+fn colony_candidates_table() {
+    let sector = load_sector(Path::new(FIXTURE)).unwrap();
+    let table = colony_table(&sector.systems, 10);
+    let mut lines = table.lines().skip(2);
+
+    // Jangala belongs to the Hegemony, so Agreus is the only planet up for grabs
+    assert_eq!(
+        lines.next(),
+        Some(
+            "| 1 | Arcadia | Agreus | barren | 100% | \
+             Ore (abundant), Rare Ore (sparse), Volatiles (trace) | 105 |"
+        )
+    );
+    assert_eq!(lines.next(), None);
+}
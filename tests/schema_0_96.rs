@@ -0,0 +1,64 @@
+//! Regression tests for saves from 0.96, where some systems and objects are first serialized
+//! in fields of other objects.
+
+use std::path::Path;
+
+use scansector::{load_save, Kind, System};
+
+const FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/campaign-0.96.xml"
+);
+
+fn system<'a>(systems: &'a [System], name: &str) -> &'a System {
+    systems
+        .iter()
+        .find(|s| s.name == name)
+        .unwrap_or_else(|| panic!("system {name} should be present"))
+}
+
+fn names(system: &System) -> Vec<&str> {
+    system.objects.iter().map(|o| o.name.as_str()).collect()
+}
+
+#[test]
+fn systems_in_fields_are_found() {
+    let systems = load_save(Path::new(FIXTURE)).unwrap();
+    let names: Vec<_> = systems.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, ["Askonia", "Both"]);
+
+    let both = system(&systems, "Both");
+    let location = both.location.expect("Both should have a location");
+    assert_eq!((location.x, location.y), (-6000.0, 3000.0));
+}
+
+#[test]
+fn nested_systems_keep_their_own_objects() {
+    let systems = load_save(Path::new(FIXTURE)).unwrap();
+
+    assert_eq!(
+        names(system(&systems, "Askonia")),
+        ["Askonia", "Askonia Jump Point"]
+    );
+    assert_eq!(
+        names(system(&systems, "Both")),
+        ["Both", "Twain's Moon", "Twain"]
+    );
+}
+
+#[test]
+fn objects_use_their_own_fields() {
+    let systems = load_save(Path::new(FIXTURE)).unwrap();
+    let both = system(&systems, "Both");
+
+    let moon = &both.objects[1];
+    assert_eq!(moon.kind, Kind::Planet);
+    assert_eq!((moon.pos.x, moon.pos.y), (1200.0, 1000.0));
+    assert_eq!(
+        moon.parent.map(|p| both.objects[p].name.as_str()),
+        Some("Twain")
+    );
+
+    let twain = &both.objects[2];
+    assert_eq!((twain.pos.x, twain.pos.y), (900.0, 900.0));
+}
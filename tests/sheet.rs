@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use scansector::load_save;
+use scansector::sheet::to_png;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/campaign.xml");
+
+/// The size and RGB pixels of a PNG.
+fn decode(png: &[u8]) -> (u32, u32, Vec<u8>) {
+    let mut reader = png::Decoder::new(png).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    pixels.truncate(info.buffer_size());
+    (info.width, info.height, pixels)
+}
+
+#[test]
+fn cards_are_laid_out_in_rows() {
+    let systems = load_save(Path::new(FIXTURE)).unwrap();
+    let red = |_: &_| eframe::egui::Color32::RED;
+
+    let (width, height, pixels) = decode(&to_png(&systems, red).unwrap());
+    assert_eq!((width, height), (8 + 3 * 328, 8 + 344));
+    // Objects are drawn in their colour, and names in light text
+    let rgb: Vec<&[u8]> = pixels.chunks(3).collect();
+    assert!(rgb.iter().any(|p| p[0] > 200 && p[1] < 50));
+    assert!(rgb.iter().any(|p| p[0] > 150 && p[1] > 150 && p[2] > 150));
+
+    let many: Vec<_> = systems.iter().cycle().take(5).cloned().collect();
+    let (width, height, _) = decode(&to_png(&many, red).unwrap());
+    assert_eq!((width, height), (8 + 4 * 328, 8 + 2 * 344));
+}